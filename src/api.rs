@@ -47,12 +47,15 @@ impl Api {
     }
 
     pub(crate) async fn is_unauthorized(&self) -> bool {
-        self.head("/clusters")
-            .await
-            .err()
-            .and_then(|e| e.downcast::<reqwest::Error>().ok())
-            .and_then(|e| e.status())
-            .map_or(false, |status| status == reqwest::StatusCode::UNAUTHORIZED)
+        matches!(self.ping().await, Err(err) if is_unauthorized(&err))
+    }
+
+    pub(crate) async fn ping(&self) -> ApiResult<()> {
+        self.head("/clusters").await
+    }
+
+    pub(crate) fn has_token(&self) -> bool {
+        self.token.is_some()
     }
 
     pub(crate) async fn create_state(&self, state: v0::CreateStateDto) -> ApiResult<v0::State> {
@@ -81,6 +84,34 @@ impl Api {
         self.del(path).await
     }
 
+    pub(crate) async fn resize_volume(
+        &self,
+        state: &v0::StateName,
+        volume: &v0::VolumeName,
+        size: v0::ResizeVolumeDto,
+    ) -> ApiResult<v0::Volume> {
+        let path = format!(
+            "/states/{state}/volumes/{volume}",
+            state = state,
+            volume = volume
+        );
+        self.patch(path, size).await
+    }
+
+    pub(crate) async fn update_volume_metadata(
+        &self,
+        state: &v0::StateName,
+        volume: &v0::VolumeName,
+        metadata: v0::UpdateMetadataDto,
+    ) -> ApiResult<v0::Volume> {
+        let path = format!(
+            "/states/{state}/volumes/{volume}",
+            state = state,
+            volume = volume
+        );
+        self.patch(path, metadata).await
+    }
+
     pub(crate) async fn set_volume_primary(
         &self,
         state: v0::StateName,
@@ -96,6 +127,19 @@ impl Api {
         self.put(path).await
     }
 
+    pub(crate) async fn unset_volume_primary(
+        &self,
+        state: v0::StateName,
+        volume: v0::VolumeName,
+    ) -> ApiResult<v0::Volume> {
+        let path = format!(
+            "/states/{state}/volumes/{volume}/activeLocation",
+            state = state,
+            volume = volume,
+        );
+        self.del(path).await
+    }
+
     pub(crate) async fn get_volume(
         &self,
         state: &v0::StateName,
@@ -124,6 +168,15 @@ impl Api {
         self.get(path).await
     }
 
+    pub(crate) async fn update_state_metadata(
+        &self,
+        name: &v0::StateName,
+        metadata: v0::UpdateMetadataDto,
+    ) -> ApiResult<v0::State> {
+        let path = format!("/states/{name}", name = name);
+        self.patch(path, metadata).await
+    }
+
     pub(crate) async fn get_all_states(&self) -> ApiResult<Vec<v0::State>> {
         self.get("/states").await
     }
@@ -133,6 +186,10 @@ impl Api {
         self.get(path).await
     }
 
+    pub(crate) async fn get_regions(&self) -> ApiResult<Vec<v0::Region>> {
+        self.get("/regions").await
+    }
+
     pub(crate) async fn get_all_clusters(&self) -> ApiResult<Vec<v0::Cluster>> {
         self.get("/clusters").await
     }
@@ -142,17 +199,29 @@ impl Api {
         name: &v0::ClusterName,
         provider: v0::Provider,
         locations: &[Location],
+        metadata: &v0::Metadata,
     ) -> ApiResult<v0::Cluster> {
         let name = name.clone();
         let locations = locations.into();
+        let metadata = metadata.clone();
         let body = v0::CreateClusterDto {
             name,
             provider,
             locations,
+            metadata,
         };
         self.post("/clusters", body).await
     }
 
+    pub(crate) async fn update_cluster_metadata(
+        &self,
+        name: &v0::ClusterName,
+        metadata: v0::UpdateMetadataDto,
+    ) -> ApiResult<v0::Cluster> {
+        let path = format!("/clusters/{name}", name = name);
+        self.patch(path, metadata).await
+    }
+
     pub(crate) async fn unregister_cluster(&self, name: v0::ClusterName) -> ApiResult<()> {
         let path = format!("/clusters/{name}", name = name);
         self.del(path).await
@@ -243,6 +312,32 @@ impl Api {
         self.put(path).await
     }
 
+    pub(crate) async fn allow_cluster(
+        &self,
+        state: &v0::StateName,
+        cluster: &v0::ClusterName,
+    ) -> ApiResult<v0::State> {
+        let path = format!(
+            "/states/{state}/allowedClusters/{cluster}",
+            state = state,
+            cluster = cluster,
+        );
+        self.put(path).await
+    }
+
+    pub(crate) async fn disallow_cluster(
+        &self,
+        state: &v0::StateName,
+        cluster: &v0::ClusterName,
+    ) -> ApiResult<v0::State> {
+        let path = format!(
+            "/states/{state}/allowedClusters/{cluster}",
+            state = state,
+            cluster = cluster,
+        );
+        self.del(path).await
+    }
+
     pub(crate) async fn unset_owner(&self, state: &v0::StateName) -> ApiResult<v0::State> {
         let path = format!("/states/{state}/owner", state = state);
         self.del(path).await
@@ -256,6 +351,11 @@ impl Api {
         self.post::<_, _, (), _>(path, None).await
     }
 
+    pub(crate) async fn revoke_cluster_token(&self, cluster: &v0::ClusterName) -> ApiResult<()> {
+        let path = format!("/clusters/{}/token", cluster);
+        self.del(path).await
+    }
+
     pub(crate) fn url(&self, path: impl fmt::Display) -> String {
         format!("{}{}", self.base, path)
     }
@@ -343,6 +443,28 @@ impl Api {
             .inspect(|output| self.inspect(output))
     }
 
+    async fn patch<P, B, T, U>(&self, path: P, body: B) -> ApiResult<U>
+    where
+        P: fmt::Display,
+        B: Into<Option<T>>,
+        T: ser::Serialize,
+        U: de::DeserializeOwned + ser::Serialize + fmt::Debug,
+    {
+        let body = body.into();
+        let url = self.url(path);
+        self.client()?
+            .patch(url)
+            .optionally_bearer_auth(self.token.as_ref())
+            .inspect()
+            .optionally_json(body.as_ref())
+            .retry()
+            .await?
+            .error_for_status2()
+            .await?
+            .try_into()
+            .inspect(|output| self.inspect(output))
+    }
+
     async fn put<P, T>(&self, path: P) -> ApiResult<T>
     where
         P: fmt::Display,
@@ -368,6 +490,16 @@ impl Api {
     }
 }
 
+pub(crate) fn is_unauthorized(err: &anyhow::Error) -> bool {
+    if let Some(err) = err.downcast_ref::<v0::Error>() {
+        err.is_unauthorized()
+    } else if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        err.status() == Some(reqwest::StatusCode::UNAUTHORIZED)
+    } else {
+        false
+    }
+}
+
 trait Optionally {
     fn optionally_bearer_auth(self, token: Option<&SecretString>) -> Self;
     fn optionally_json<T>(self, body: Option<&T>) -> Self
@@ -0,0 +1,329 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str;
+
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Show;
+
+use super::*;
+
+const CLUSTER_NAME: &str = "cluster-name";
+const DEFAULT_STATE: &str = "default-state";
+const API_URL: &str = "api-url";
+const CLEANUP_GRACE: &str = "cleanup-grace";
+const LOG_LEVEL: &str = "log-level";
+
+const KEYS: &[&str] = &[
+    CLUSTER_NAME,
+    DEFAULT_STATE,
+    API_URL,
+    CLEANUP_GRACE,
+    LOG_LEVEL,
+];
+
+/// Statehub in-cluster components settings, as stored in the statehub configmap
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct ClusterAgentConfig {
+    cluster_name: v0::ClusterName,
+    default_state: String,
+    api_url: String,
+    cleanup_grace: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    log_level: Option<LogLevel>,
+    /// Keys this version of statehub CLI does not know about, kept as is
+    #[serde(flatten)]
+    other: BTreeMap<String, String>,
+}
+
+/// Single `key=value` configmap setting
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum AgentSetting {
+    ClusterName(v0::ClusterName),
+    DefaultState(String),
+    ApiUrl(String),
+    CleanupGrace(Duration),
+    LogLevel(LogLevel),
+}
+
+/// Duration in the format in-cluster components understand, e.g. "600s" or "1h30m"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Duration(String);
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl ClusterAgentConfig {
+    pub(crate) fn new(cluster_name: &v0::ClusterName, default_state: &str, api_url: &str) -> Self {
+        Self {
+            cluster_name: cluster_name.clone(),
+            default_state: default_state.to_string(),
+            api_url: api_url.to_string(),
+            cleanup_grace: Duration(STATEHUB_DEFAULT_CLEANUP_GRACE.to_string()),
+            log_level: None,
+            other: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn cluster_name(&self) -> &v0::ClusterName {
+        &self.cluster_name
+    }
+
+    pub(crate) fn with_settings<'a>(
+        mut self,
+        settings: impl IntoIterator<Item = &'a AgentSetting>,
+    ) -> Self {
+        for setting in settings {
+            self.set(setting.clone());
+        }
+        self
+    }
+
+    pub(crate) fn set(&mut self, setting: AgentSetting) {
+        match setting {
+            AgentSetting::ClusterName(name) => self.cluster_name = name,
+            AgentSetting::DefaultState(state) => self.default_state = state,
+            AgentSetting::ApiUrl(url) => self.api_url = url,
+            AgentSetting::CleanupGrace(grace) => self.cleanup_grace = grace,
+            AgentSetting::LogLevel(level) => self.log_level = Some(level),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        match key {
+            CLUSTER_NAME => Some(self.cluster_name.to_string()),
+            DEFAULT_STATE => Some(self.default_state.clone()),
+            API_URL => Some(self.api_url.clone()),
+            CLEANUP_GRACE => Some(self.cleanup_grace.to_string()),
+            LOG_LEVEL => self.log_level.map(|level| level.to_string()),
+            other => self.other.get(other).cloned(),
+        }
+    }
+
+    pub(crate) fn data(&self) -> BTreeMap<String, String> {
+        let mut data = self.other.clone();
+        data.extend(
+            KEYS.iter()
+                .filter_map(|key| self.get(key).map(|value| (key.to_string(), value))),
+        );
+        data
+    }
+
+    /// Parse live configmap data, validating all the known keys
+    pub(crate) fn from_data(data: &BTreeMap<String, String>) -> anyhow::Result<Self> {
+        let cluster_name = data
+            .get(CLUSTER_NAME)
+            .ok_or_else(|| anyhow::anyhow!("Missing {} in statehub configmap", CLUSTER_NAME))?;
+        let mut config = Self::new(&v0::ClusterName::from(cluster_name.as_str()), "", "");
+        for (key, value) in data {
+            if KEYS.contains(&key.as_str()) {
+                config.set(AgentSetting::new(key, value)?);
+            } else {
+                log::warn!("Unknown statehub configmap key {}", key);
+                config.other.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl AgentSetting {
+    pub(crate) fn new(key: &str, value: &str) -> anyhow::Result<Self> {
+        let setting = match key {
+            CLUSTER_NAME => Self::ClusterName(value.into()),
+            DEFAULT_STATE => Self::DefaultState(value.to_string()),
+            API_URL => Self::ApiUrl(value.to_string()),
+            CLEANUP_GRACE => Self::CleanupGrace(value.parse()?),
+            LOG_LEVEL => Self::LogLevel(value.parse()?),
+            other => anyhow::bail!(
+                "Unknown configmap setting {}, expected one of {}",
+                other,
+                KEYS.join(", ")
+            ),
+        };
+        Ok(setting)
+    }
+
+    pub(crate) fn key(&self) -> &'static str {
+        match self {
+            Self::ClusterName(_) => CLUSTER_NAME,
+            Self::DefaultState(_) => DEFAULT_STATE,
+            Self::ApiUrl(_) => API_URL,
+            Self::CleanupGrace(_) => CLEANUP_GRACE,
+            Self::LogLevel(_) => LOG_LEVEL,
+        }
+    }
+
+    /// Cluster name and API URL are what the cluster is registered as and with,
+    /// so they only come from the registration itself
+    pub(crate) fn is_identity(&self) -> bool {
+        matches!(self, Self::ClusterName(_) | Self::ApiUrl(_))
+    }
+}
+
+impl str::FromStr for AgentSetting {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid setting {}, expected key=value", s))?;
+        Self::new(key.trim(), value.trim())
+    }
+}
+
+impl str::FromStr for Duration {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow::anyhow!("Invalid duration {:?}, expected e.g. 600s or 1h30m", s);
+        let mut rest = s;
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            if digits == 0 {
+                return Err(invalid());
+            }
+            rest = &rest[digits..];
+            let unit = ["ms", "s", "m", "h"]
+                .iter()
+                .find(|unit| rest.starts_with(*unit))
+                .ok_or_else(invalid)?;
+            rest = &rest[unit.len()..];
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for Duration {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Duration> for String {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl LogLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+impl str::FromStr for LogLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" | "warning" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            other => anyhow::bail!(
+                "Invalid log level: {}, expected error, warn, info, debug or trace",
+                other
+            ),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl Show for ClusterAgentConfig {
+    fn show(&self) -> String {
+        self.data()
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration() {
+        for valid in &["600s", "10m", "1h30m", "250ms", "1h0m5s"] {
+            assert!(valid.parse::<Duration>().is_ok(), "{}", valid);
+        }
+        for invalid in &["", "600", "s", "ten minutes", "10x", "-5s", "1.5h"] {
+            assert!(invalid.parse::<Duration>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn setting() {
+        let setting = "log-level=debug".parse::<AgentSetting>().unwrap();
+        assert_eq!(setting, AgentSetting::LogLevel(LogLevel::Debug));
+        assert!("log-level=loud".parse::<AgentSetting>().is_err());
+        assert!("cleanup-grace=forever".parse::<AgentSetting>().is_err());
+        assert!("unknown=value".parse::<AgentSetting>().is_err());
+        assert!("log-level".parse::<AgentSetting>().is_err());
+        assert!(!setting.is_identity());
+        assert!("cluster-name=bravo"
+            .parse::<AgentSetting>()
+            .unwrap()
+            .is_identity());
+        assert!("api-url=https://example.com"
+            .parse::<AgentSetting>()
+            .unwrap()
+            .is_identity());
+    }
+
+    #[test]
+    fn data_roundtrip() {
+        let config = ClusterAgentConfig::new(&"alfa".into(), "default", "https://api.statehub.io")
+            .with_settings(&[
+                AgentSetting::CleanupGrace("5m".parse().unwrap()),
+                AgentSetting::LogLevel(LogLevel::Trace),
+            ]);
+        let mut data = config.data();
+        assert_eq!(data["cleanup-grace"], "5m");
+        assert_eq!(data["log-level"], "trace");
+        data.insert("future-key".to_string(), "value".to_string());
+        let parsed = ClusterAgentConfig::from_data(&data).unwrap();
+        assert_eq!(parsed.data(), data);
+    }
+}
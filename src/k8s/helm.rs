@@ -6,34 +6,49 @@
 use std::io;
 use std::process::{Command, ExitStatus};
 
+use serde::{Deserialize, Serialize};
+use serde_json as json;
 use tokio::process::Command as AsyncCmd;
 
 use crate::traits::Show;
 use crate::v0;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HelmRelease {
+    pub(crate) name: String,
+    pub(crate) status: String,
+    pub(crate) chart: String,
+    pub(crate) app_version: String,
+}
+
+#[derive(Clone, Debug)]
 pub(crate) enum Helm {
     Skip {
         namespace: String,
         default_state: Option<String>,
+        context: Option<String>,
     },
     Do {
         namespace: String,
         default_state: Option<String>,
+        context: Option<String>,
     },
 }
 
 impl Helm {
     pub(crate) fn new(namespace: String, default_state: Option<String>, skip_helm: bool) -> Self {
+        let context = None;
         if skip_helm {
             Self::Skip {
                 namespace,
                 default_state,
+                context,
             }
         } else {
             Self::Do {
                 namespace,
                 default_state,
+                context,
             }
         }
     }
@@ -43,14 +58,46 @@ impl Helm {
             Self::Do {
                 namespace,
                 default_state,
+                context,
             } => Self::Skip {
                 namespace,
                 default_state,
+                context,
             },
             other => other,
         }
     }
 
+    pub(crate) fn with_context(self, context: Option<String>) -> Self {
+        match self {
+            Self::Skip {
+                namespace,
+                default_state,
+                ..
+            } => Self::Skip {
+                namespace,
+                default_state,
+                context,
+            },
+            Self::Do {
+                namespace,
+                default_state,
+                ..
+            } => Self::Do {
+                namespace,
+                default_state,
+                context,
+            },
+        }
+    }
+
+    pub(crate) fn context(&self) -> Option<&str> {
+        match self {
+            Self::Skip { context, .. } => context.as_deref(),
+            Self::Do { context, .. } => context.as_deref(),
+        }
+    }
+
     pub(crate) fn default_state(&self) -> Option<&str> {
         match self {
             Helm::Skip { default_state, .. } => default_state.as_deref(),
@@ -96,16 +143,7 @@ impl Helm {
             .helm
             .iter()
             .map(|helm| {
-                let mut cmd = Command::new("helm");
-                cmd.arg("install")
-                    .arg(&helm.chart)
-                    .arg("--namespace")
-                    .arg(self.namespace())
-                    .arg("--repo")
-                    .arg(&helm.repo)
-                    .arg("--version")
-                    .arg(&helm.version)
-                    .arg(&helm.chart);
+                let mut cmd = self.install(helm);
                 for (param, value) in &helm.parameters {
                     cmd.arg("--set").arg(format!("{}={}", param, value));
                 }
@@ -114,6 +152,51 @@ impl Helm {
             .collect()
     }
 
+    pub(crate) fn values_command(&self, helm: &v0::Helm, values: &str) -> Command {
+        let mut cmd = self.install(helm);
+        cmd.arg("--values").arg(values);
+        cmd
+    }
+
+    fn install(&self, helm: &v0::Helm) -> Command {
+        let mut cmd = Command::new("helm");
+        cmd.arg("install")
+            .arg(&helm.chart)
+            .arg("--namespace")
+            .arg(self.namespace())
+            .arg("--repo")
+            .arg(&helm.repo)
+            .arg("--version")
+            .arg(&helm.version)
+            .arg(&helm.chart);
+        if let Some(context) = self.context() {
+            cmd.arg("--kube-context").arg(context);
+        }
+        cmd
+    }
+
+    /// Installed helm release for this chart, if any
+    pub(crate) async fn release(&self, helm: &v0::Helm) -> anyhow::Result<Option<HelmRelease>> {
+        let mut cmd = Command::new("helm");
+        cmd.arg("list")
+            .arg("--namespace")
+            .arg(self.namespace())
+            .arg("--filter")
+            .arg(format!("^{}$", helm.chart))
+            .arg("--output")
+            .arg("json");
+        if let Some(context) = self.context() {
+            cmd.arg("--kube-context").arg(context);
+        }
+
+        let (status, stdout, stderr) = self.exec(cmd).await?;
+        anyhow::ensure!(status.success(), stderr);
+        let release = json::from_str::<Vec<HelmRelease>>(&stdout)?
+            .into_iter()
+            .find(|release| release.name == helm.chart);
+        Ok(release)
+    }
+
     async fn exec(&self, command: Command) -> io::Result<(ExitStatus, String, String)> {
         let output = AsyncCmd::from(command).output().await?;
         let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
@@ -121,3 +204,13 @@ impl Helm {
         Ok((output.status, stdout, stderr))
     }
 }
+
+impl HelmRelease {
+    /// Chart version, `helm list` reports it as a suffix of the chart name
+    pub(crate) fn version(&self) -> &str {
+        self.chart
+            .strip_prefix(self.name.as_str())
+            .and_then(|version| version.strip_prefix('-'))
+            .unwrap_or(&self.chart)
+    }
+}
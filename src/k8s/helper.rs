@@ -4,13 +4,24 @@
 //
 
 use std::collections::HashMap;
+use std::fmt;
 
 use k8s_openapi::api::core::v1::Node;
 use kube::api::ResourceExt;
 
+use crate::v0;
+
 const K8S_TOPOLOGY_REGION: &str = "topology.kubernetes.io/region";
 const K8S_TOPOLOGY_ZONE: &str = "topology.kubernetes.io/zone";
 const AKS_CLUSTER: &str = "kubernetes.azure.com/cluster";
+const AKS_NODE_RESOURCE_GROUP_PREFIX: &str = "MC_";
+const EKS_NODEGROUP: &str = "eks.amazonaws.com/nodegroup";
+const EKS_COMPUTE_TYPE: &str = "eks.amazonaws.com/compute-type";
+const GKE_NODEPOOL: &str = "cloud.google.com/gke-nodepool";
+const KOPS_INSTANCEGROUP: &str = "kops.k8s.io/instancegroup";
+const AWS_PROVIDER_ID: &str = "aws://";
+const AZURE_PROVIDER_ID: &str = "azure://";
+const GCE_PROVIDER_ID: &str = "gce://";
 
 trait LabelsExt: ResourceExt {
     fn label(&self, label: impl AsRef<str>) -> Option<&str> {
@@ -52,13 +63,213 @@ pub(super) fn group_nodes_by_zone(
         })
 }
 
-pub(super) fn is_aks(nodes: impl IntoIterator<Item = Node>) -> bool {
-    nodes
-        .into_iter()
-        .filter_map(|node| node.label(AKS_CLUSTER).map(String::from))
-        .any(|label| label.starts_with("MC_"))
+/// Detected cluster provider along with the reason for choosing it
+#[derive(Debug)]
+pub(crate) struct ProviderDetection {
+    pub(crate) provider: v0::Provider,
+    pub(crate) reason: String,
+}
+
+impl ProviderDetection {
+    fn new(provider: v0::Provider, reason: impl fmt::Display) -> Self {
+        let reason = reason.to_string();
+        Self { provider, reason }
+    }
+}
+
+impl fmt::Display for ProviderDetection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#} ({})", self.provider, self.reason)
+    }
+}
+
+pub(super) fn detect_provider(nodes: &[Node]) -> ProviderDetection {
+    let aks = nodes.iter().find_map(|node| {
+        node.label(AKS_CLUSTER)
+            .filter(|value| value.starts_with(AKS_NODE_RESOURCE_GROUP_PREFIX))
+            .map(|value| (node, value))
+    });
+    if let Some((node, value)) = aks {
+        let reason = format!("node {} has label {}={}", node.name(), AKS_CLUSTER, value);
+        return ProviderDetection::new(v0::Provider::Aks, reason);
+    }
+
+    let labels = [
+        (EKS_NODEGROUP, v0::Provider::Eks),
+        (EKS_COMPUTE_TYPE, v0::Provider::Eks),
+        (KOPS_INSTANCEGROUP, v0::Provider::Kops),
+    ];
+    for (label, provider) in labels.iter() {
+        if let Some((node, value)) = nodes
+            .iter()
+            .find_map(|node| node.label(label).map(|value| (node, value)))
+        {
+            let reason = format!("node {} has label {}={}", node.name(), label, value);
+            return ProviderDetection::new(*provider, reason);
+        }
+    }
+
+    // GKE is recognized, but statehub does not support it as a provider yet
+    if let Some((node, value)) = nodes
+        .iter()
+        .find_map(|node| node.label(GKE_NODEPOOL).map(|value| (node, value)))
+    {
+        let reason = format!(
+            "node {} has label {}={}, GKE is not supported yet, treating it as generic",
+            node.name(),
+            GKE_NODEPOOL,
+            value
+        );
+        return ProviderDetection::new(v0::Provider::Generic, reason);
+    }
+
+    let clouds = [
+        (AWS_PROVIDER_ID, "AWS", "EKS or kops"),
+        (AZURE_PROVIDER_ID, "Azure", "AKS"),
+        (GCE_PROVIDER_ID, "GCP", "GKE"),
+    ];
+    for (prefix, cloud, managed) in clouds.iter() {
+        if let Some(node) = nodes
+            .iter()
+            .find(|node| matches!(node.provider_id(), Some(id) if id.starts_with(prefix)))
+        {
+            let reason = format!(
+                "node {} runs on {} (providerID {}), but no {} labels found",
+                node.name(),
+                cloud,
+                node.provider_id().unwrap_or_default(),
+                managed
+            );
+            return ProviderDetection::new(v0::Provider::Generic, reason);
+        }
+    }
+
+    let reason = if nodes.is_empty() {
+        "no nodes found"
+    } else {
+        "no managed provider labels or known providerID found"
+    };
+    ProviderDetection::new(v0::Provider::Generic, reason)
+}
+
+trait NodeExt {
+    fn provider_id(&self) -> Option<&str>;
 }
 
-pub(super) fn is_eks() -> bool {
-    true
+impl NodeExt for Node {
+    fn provider_id(&self) -> Option<&str> {
+        self.spec.as_ref()?.provider_id.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json as json;
+
+    use super::*;
+
+    fn node(name: &str, labels: json::Value, provider_id: Option<&str>) -> Node {
+        json::from_value(json::json!({
+            "metadata": {
+                "name": name,
+                "labels": labels,
+            },
+            "spec": {
+                "providerID": provider_id,
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn detect_aks() {
+        let nodes = [node(
+            "aks-nodepool1-0",
+            json::json!({ "kubernetes.azure.com/cluster": "MC_rg_cluster_eastus" }),
+            Some("azure:///subscriptions/0/resourceGroups/mc_rg/vm-0"),
+        )];
+        let detection = detect_provider(&nodes);
+        assert_eq!(detection.provider, v0::Provider::Aks);
+        assert!(detection.reason.contains("aks-nodepool1-0"));
+    }
+
+    #[test]
+    fn detect_eks() {
+        let nodes = [
+            node(
+                "ip-10-0-0-1",
+                json::json!({}),
+                Some("aws:///us-east-1a/i-0"),
+            ),
+            node(
+                "ip-10-0-0-2",
+                json::json!({ "eks.amazonaws.com/nodegroup": "ng-1" }),
+                Some("aws:///us-east-1a/i-1"),
+            ),
+        ];
+        let detection = detect_provider(&nodes);
+        assert_eq!(detection.provider, v0::Provider::Eks);
+        assert!(detection
+            .reason
+            .contains("eks.amazonaws.com/nodegroup=ng-1"));
+    }
+
+    #[test]
+    fn detect_eks_fargate() {
+        let nodes = [node(
+            "fargate-ip-10-0-0-1",
+            json::json!({ "eks.amazonaws.com/compute-type": "fargate" }),
+            None,
+        )];
+        assert_eq!(detect_provider(&nodes).provider, v0::Provider::Eks);
+    }
+
+    #[test]
+    fn detect_kops() {
+        let nodes = [node(
+            "ip-10-0-0-1",
+            json::json!({ "kops.k8s.io/instancegroup": "nodes-us-east-1a" }),
+            Some("aws:///us-east-1a/i-0"),
+        )];
+        assert_eq!(detect_provider(&nodes).provider, v0::Provider::Kops);
+    }
+
+    #[test]
+    fn detect_gke() {
+        let nodes = [node(
+            "gke-cluster-default-pool-0",
+            json::json!({ "cloud.google.com/gke-nodepool": "default-pool" }),
+            Some("gce://project/us-central1-a/gke-cluster-default-pool-0"),
+        )];
+        let detection = detect_provider(&nodes);
+        assert_eq!(detection.provider, v0::Provider::Generic);
+        assert!(detection.reason.contains("GKE is not supported"));
+    }
+
+    #[test]
+    fn detect_self_managed_aws() {
+        let nodes = [node(
+            "ip-10-0-0-1",
+            json::json!({}),
+            Some("aws:///us-east-1a/i-0"),
+        )];
+        let detection = detect_provider(&nodes);
+        assert_eq!(detection.provider, v0::Provider::Generic);
+        assert!(detection.reason.contains("AWS"));
+    }
+
+    #[test]
+    fn detect_generic() {
+        let nodes = [node(
+            "kind-control-plane",
+            json::json!({}),
+            Some("kind://docker/kind"),
+        )];
+        let detection = detect_provider(&nodes);
+        assert_eq!(detection.provider, v0::Provider::Generic);
+        assert_eq!(
+            detection.reason,
+            "no managed provider labels or known providerID found"
+        );
+    }
 }
@@ -35,11 +35,10 @@ impl KubeconfigExt for Kubeconfig {
     //     Ok(contexts)
     // }
 
+    /// Only context names count, clusters cannot be used as `--context`
     fn contains(&self, name: impl AsRef<str>) -> bool {
         let name = name.as_ref();
-        let all_contexts = self.contexts.iter().map(|context| &context.name);
-        let all_clusters = self.clusters.iter().map(|cluster| &cluster.name);
-        all_contexts.chain(all_clusters).any(|this| this == name)
+        self.contexts.iter().any(|context| context.name == name)
     }
 
     fn current_context(&self) -> Option<&str> {
@@ -0,0 +1,289 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::fmt;
+use std::str;
+
+use serde::Serialize;
+
+use crate::traits::Show;
+
+use super::*;
+
+const FLUX_HELM_API_VERSION: &str = "helm.toolkit.fluxcd.io/v2beta1";
+const FLUX_SOURCE_API_VERSION: &str = "source.toolkit.fluxcd.io/v1beta1";
+const ARGOCD_API_VERSION: &str = "argoproj.io/v1alpha1";
+const ARGOCD_NAMESPACE: &str = "argocd";
+const GITOPS_INTERVAL: &str = "10m";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum GitOps {
+    Flux,
+    ArgoCd,
+}
+
+impl GitOps {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flux => "flux",
+            Self::ArgoCd => "argocd",
+        }
+    }
+}
+
+impl fmt::Display for GitOps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl str::FromStr for GitOps {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flux" => Ok(Self::Flux),
+            "argocd" | "argo" => Ok(Self::ArgoCd),
+            other => anyhow::bail!("Invalid GitOps flavor: {}", other),
+        }
+    }
+}
+
+/// Single file worth of rendered k8s objects
+#[derive(Debug)]
+pub(crate) struct Manifest {
+    pub(crate) file: String,
+    pub(crate) text: String,
+}
+
+impl Manifest {
+    fn new(file: impl ToString, text: impl ToString) -> Self {
+        let file = file.to_string();
+        let text = text.to_string();
+        Self { file, text }
+    }
+
+    fn yaml<'a, T>(
+        file: impl ToString,
+        objects: impl IntoIterator<Item = &'a T>,
+    ) -> anyhow::Result<Self>
+    where
+        T: Serialize + 'a,
+    {
+        let text = objects
+            .into_iter()
+            .map(serde_yaml::to_string)
+            .collect::<Result<String, _>>()?;
+        Ok(Self::new(file, text))
+    }
+}
+
+/// Render everything `register-cluster` would otherwise create or execute
+/// in the cluster, so it can be committed to a GitOps repository instead.
+pub(crate) fn cluster_manifests(
+    helm: &Helm,
+    cluster: &v0::Cluster,
+    api: &str,
+    token: Option<&str>,
+    gitops: GitOps,
+) -> anyhow::Result<Vec<Manifest>> {
+    let namespace = helm.namespace();
+    let default_state = helm.default_state().unwrap_or("");
+    let mut manifests = vec![];
+    let mut commands = vec![];
+
+    for chart in &cluster.helm {
+        let file = values_file(chart, cluster.helm.len());
+        let values = helm_values(&chart.parameters);
+        let release = match gitops {
+            GitOps::Flux => flux_helm_release(chart, namespace, &values),
+            GitOps::ArgoCd => argocd_application(chart, namespace, &values)?,
+        };
+        commands.push(helm.values_command(chart, &file));
+        manifests.push(Manifest::yaml(file, &[values])?);
+        manifests.push(Manifest::yaml(release_file(chart, gitops), &release)?);
+    }
+
+    let config = ClusterAgentConfig::new(&cluster.name, default_state, api);
+    let configmap = configmap(namespace, STATEHUB_CLUSTER_CONFIGMAP_NAME, &config)?;
+    manifests.push(Manifest::yaml("configmap.yaml", &[configmap])?);
+
+    if let Some(token) = token {
+        let secret = secret(
+            namespace,
+            STATEHUB_CLUSTER_TOKEN_SECRET_TYPE,
+            STATEHUB_CLUSTER_TOKEN_SECRET_NAME,
+            token,
+        )?;
+        manifests.push(Manifest::yaml("secret.yaml", &[secret])?);
+    }
+
+    let script = format!("#!/bin/sh\n\n{}\n", commands.show());
+    manifests.push(Manifest::new("helm-install.sh", script));
+
+    Ok(manifests)
+}
+
+fn values_file(chart: &v0::Helm, charts: usize) -> String {
+    if charts > 1 {
+        format!("{}-values.yaml", chart.chart)
+    } else {
+        String::from("values.yaml")
+    }
+}
+
+fn release_file(chart: &v0::Helm, gitops: GitOps) -> String {
+    match gitops {
+        GitOps::Flux => format!("{}-helmrelease.yaml", chart.chart),
+        GitOps::ArgoCd => format!("{}-application.yaml", chart.chart),
+    }
+}
+
+/// Turn `--set` style parameters into nested helm values,
+/// using the same scalar typing rules as `helm --set`
+pub(crate) fn helm_values(parameters: &HashMap<String, String>) -> json::Value {
+    let mut values = json::Map::new();
+    let mut parameters = parameters.iter().collect::<Vec<_>>();
+    parameters.sort();
+
+    for (key, value) in parameters {
+        let path = key.split('.').collect::<Vec<_>>();
+        insert_helm_value(&mut values, &path, helm_scalar(value));
+    }
+
+    json::Value::Object(values)
+}
+
+fn insert_helm_value(node: &mut json::Map<String, json::Value>, path: &[&str], value: json::Value) {
+    match path {
+        [] => {}
+        [key] => {
+            node.insert(key.to_string(), value);
+        }
+        [key, rest @ ..] => {
+            let next = node.entry(*key).or_insert_with(|| json::json!({}));
+            if !next.is_object() {
+                log::warn!("Helm parameter {} overrides value {}", key, next);
+                *next = json::json!({});
+            }
+            if let json::Value::Object(next) = next {
+                insert_helm_value(next, rest, value);
+            }
+        }
+    }
+}
+
+fn helm_scalar(value: &str) -> json::Value {
+    match value {
+        "true" => json::Value::Bool(true),
+        "false" => json::Value::Bool(false),
+        "null" => json::Value::Null,
+        other => other
+            .parse::<i64>()
+            .map_or_else(|_| json::Value::from(other), json::Value::from),
+    }
+}
+
+fn flux_helm_release(chart: &v0::Helm, namespace: &str, values: &json::Value) -> Vec<json::Value> {
+    let repository = json::json!({
+        "apiVersion": FLUX_SOURCE_API_VERSION,
+        "kind": "HelmRepository",
+        "metadata": {
+            "name": chart.chart,
+            "namespace": namespace,
+        },
+        "spec": {
+            "interval": GITOPS_INTERVAL,
+            "url": chart.repo,
+        }
+    });
+    let release = json::json!({
+        "apiVersion": FLUX_HELM_API_VERSION,
+        "kind": "HelmRelease",
+        "metadata": {
+            "name": chart.chart,
+            "namespace": namespace,
+        },
+        "spec": {
+            "interval": GITOPS_INTERVAL,
+            "targetNamespace": namespace,
+            "chart": {
+                "spec": {
+                    "chart": chart.chart,
+                    "version": chart.version,
+                    "sourceRef": {
+                        "kind": "HelmRepository",
+                        "name": chart.chart,
+                        "namespace": namespace,
+                    }
+                }
+            },
+            "values": values,
+        }
+    });
+    vec![repository, release]
+}
+
+fn argocd_application(
+    chart: &v0::Helm,
+    namespace: &str,
+    values: &json::Value,
+) -> anyhow::Result<Vec<json::Value>> {
+    let values = serde_yaml::to_string(values)?;
+    let application = json::json!({
+        "apiVersion": ARGOCD_API_VERSION,
+        "kind": "Application",
+        "metadata": {
+            "name": chart.chart,
+            "namespace": ARGOCD_NAMESPACE,
+        },
+        "spec": {
+            "project": "default",
+            "source": {
+                "repoURL": chart.repo,
+                "chart": chart.chart,
+                "targetRevision": chart.version,
+                "helm": {
+                    "values": values,
+                }
+            },
+            "destination": {
+                "server": "https://kubernetes.default.svc",
+                "namespace": namespace,
+            },
+            "syncPolicy": {
+                "automated": {},
+            }
+        }
+    });
+    Ok(vec![application])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helm_values() {
+        let parameters = vec![
+            ("cluster.name", "alfa"),
+            ("cluster.id", "42"),
+            ("api.insecure", "false"),
+            ("image", "statehub/csi"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        let values = super::helm_values(&parameters);
+        assert_eq!(
+            values,
+            json::json!({
+                "api": { "insecure": false },
+                "cluster": { "id": 42, "name": "alfa" },
+                "image": "statehub/csi",
+            })
+        );
+    }
+}
@@ -5,31 +5,53 @@
 
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
 
-use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, Pod, Secret};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, PersistentVolumeClaim, Pod, Secret};
 use kube::api::{self, Api};
 // use kube::api::{Api, ListParams, PostParams, Resource, WatchEvent};
-use kube::config::Kubeconfig;
-use kube::{Client, ResourceExt};
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::{Client, Config, ResourceExt};
+use serde::de::DeserializeOwned;
 use serde_json as json;
 
 use crate::v0;
 use crate::Location;
 
-pub(crate) use helm::Helm;
-use helper::{group_nodes_by_region, group_nodes_by_zone, is_aks, is_eks};
+pub(crate) use agent::{AgentSetting, ClusterAgentConfig, Duration, LogLevel};
+pub(crate) use helm::{Helm, HelmRelease};
+pub(crate) use helper::ProviderDetection;
+use helper::{detect_provider, group_nodes_by_region, group_nodes_by_zone};
 use kubeconfig::KubeconfigExt;
+pub(crate) use manifests::{cluster_manifests, GitOps};
+pub(crate) use storage::{apply_storage_class, set_default_storage_class};
+pub(crate) use volume::{
+    get_volume_claims, get_volume_consumers, resize_volume_claim, VolumeConsumer,
+};
 
+mod agent;
 mod helm;
 mod helper;
 mod kubeconfig;
+mod manifests;
 mod show;
+mod storage;
+mod volume;
 
 const DEFAULT_NS: &str = "default";
 const KUBE_SYSTEM_NS: &str = "kube-system";
 const STATEHUB_CLUSTER_TOKEN_SECRET_TYPE: &str = "statehub.io/cluster-token";
 const STATEHUB_CLUSTER_TOKEN_SECRET_NAME: &str = "statehub-cluster-token";
+const STATEHUB_CLUSTER_TOKEN_ISSUED_ANNOTATION: &str = "statehub.io/issued-at";
 const STATEHUB_CLUSTER_CONFIGMAP_NAME: &str = "statehub";
+const STATEHUB_FIELD_MANAGER: &str = "statehub";
+const STATEHUB_CLI_VERSION_ANNOTATION: &str = "statehub.io/cli-version";
+const STATEHUB_CLUSTER_ANNOTATION: &str = "statehub.io/cluster";
+const STATEHUB_STATE_ANNOTATION: &str = "statehub.io/state";
+const STATEHUB_CSI_DRIVER: &str = "csi.statehub.io";
 const STATEHUB_DEFAULT_CLEANUP_GRACE: &str = "600s";
 
 pub(crate) struct Kubectl {
@@ -38,18 +60,31 @@ pub(crate) struct Kubectl {
 }
 
 impl Kubectl {
-    async fn with_namespace(namespace: &str) -> anyhow::Result<Self> {
-        let client = Client::try_default().await?;
+    async fn with_namespace(context: Option<&str>, namespace: &str) -> anyhow::Result<Self> {
+        let client = Self::client(context).await?;
         let namespace = namespace.to_string();
         Ok(Self { client, namespace })
     }
 
-    pub(crate) async fn default() -> anyhow::Result<Self> {
-        Self::with_namespace(DEFAULT_NS).await
+    pub(crate) async fn default(context: Option<&str>) -> anyhow::Result<Self> {
+        Self::with_namespace(context, DEFAULT_NS).await
     }
 
-    pub(crate) async fn kube_system() -> anyhow::Result<Self> {
-        Self::with_namespace(KUBE_SYSTEM_NS).await
+    pub(crate) async fn kube_system(context: Option<&str>) -> anyhow::Result<Self> {
+        Self::with_namespace(context, KUBE_SYSTEM_NS).await
+    }
+
+    async fn client(context: Option<&str>) -> anyhow::Result<Client> {
+        if let Some(context) = context {
+            let options = KubeConfigOptions {
+                context: Some(context.to_string()),
+                ..KubeConfigOptions::default()
+            };
+            let config = Config::from_kubeconfig(&options).await?;
+            Ok(Client::try_from(config)?)
+        } else {
+            Ok(Client::try_default().await?)
+        }
     }
 
     async fn all_namespaces(&self) -> anyhow::Result<impl IntoIterator<Item = Namespace>> {
@@ -84,57 +119,63 @@ impl Kubectl {
         Ok(namespace)
     }
 
-    async fn create_configmap(
+    /// Server-side apply, creates the configmap or takes over the existing one
+    async fn apply_configmap(
         &self,
         name: &str,
-        cluster_name: &v0::ClusterName,
-        default_state: &str,
-        api: &str,
+        config: &ClusterAgentConfig,
     ) -> anyhow::Result<ConfigMap> {
         let configmaps = self.configmaps();
-        let configmap = json::from_value(json::json!({
-            "apiVerion": "v1",
-            "kind": "ConfigMap",
-            "metadata": {
-                "name": name,
-                "namespace": self.namespace,
-            },
-            "data": {
-                "cluster-name": cluster_name,
-                "default-state": default_state,
-                "api-url": api,
-                "cleanup-grace": STATEHUB_DEFAULT_CLEANUP_GRACE,
-            }
-        }))?;
-        let pp = self.post_params();
-        let configmap = configmaps.create(&pp, &configmap).await?;
-
+        let configmap = configmap(&self.namespace, name, config)?;
+        let pp = self.apply_params();
+        let configmap = configmaps
+            .patch(name, &pp, &api::Patch::Apply(&configmap))
+            .await?;
         Ok(configmap)
     }
 
-    async fn create_secret(
-        &self,
-        r#type: &str,
-        name: &str,
-        secret: &str,
-    ) -> anyhow::Result<Secret> {
+    /// Server-side apply, creates the secret or replaces it in place
+    async fn apply_secret(&self, r#type: &str, name: &str, secret: &str) -> anyhow::Result<Secret> {
         let secrets = self.secrets();
-        let secret = json::from_value(json::json!({
-            "apiVerion": "v1",
-            "kind": "Secret",
-            "metadata": {
-                "name": name,
-            },
-            "type": r#type,
-            "data": {
-                "cluster-token": base64::encode(secret),
-            }
-        }))?;
-        let pp = self.post_params();
-        let secret = secrets.create(&pp, &secret).await?;
+        let secret = self::secret(&self.namespace, r#type, name, secret)?;
+        let pp = self.apply_params();
+        let secret = secrets
+            .patch(name, &pp, &api::Patch::Apply(&secret))
+            .await?;
         Ok(secret)
     }
 
+    /// Same as `kubectl rollout restart`, bump pod template annotation
+    /// to make the controller replace all the pods
+    async fn restart_workloads<K>(&self, workloads: Api<K>) -> anyhow::Result<Vec<String>>
+    where
+        K: Clone + DeserializeOwned + fmt::Debug + kube::Resource,
+    {
+        let lp = self.list_params();
+        let pp = self.patch_params();
+        let patch = json::json!({
+            "spec": {
+                "template": {
+                    "metadata": {
+                        "annotations": {
+                            "kubectl.kubernetes.io/restartedAt": Utc::now().to_rfc3339(),
+                        }
+                    }
+                }
+            }
+        });
+        let mut restarted = vec![];
+        for workload in workloads.list(&lp).await? {
+            let name = workload.name();
+            log::info!("Restarting {}", name);
+            workloads
+                .patch(&name, &pp, &api::Patch::Merge(&patch))
+                .await?;
+            restarted.push(name);
+        }
+        Ok(restarted)
+    }
+
     async fn delete_secret(&self, secret: &str) -> anyhow::Result<()> {
         log::info!("Deleting secret {}", secret);
         let secrets = self.secrets();
@@ -148,17 +189,27 @@ impl Kubectl {
         Ok(())
     }
 
-    async fn delete_configmap(&self, configmap: &str) -> anyhow::Result<()> {
-        log::info!("Deleting configmap {}", configmap);
-        let configmaps = self.configmaps();
-        let dp = self.delete_params();
-        configmaps
-            .delete(configmap, &dp)
-            .await?
-            .map_left(|cm| log::info!("Delete in progress {:#?}", cm))
-            .map_right(|cm| log::info!("Delete succeeded: {:#?}", cm));
+    async fn all_persistent_volume_claims(
+        &self,
+    ) -> anyhow::Result<impl IntoIterator<Item = PersistentVolumeClaim>> {
+        let pvcs = self.persistent_volume_claims();
+        let lp = self.list_params();
+        Ok(pvcs.list(&lp).await?)
+    }
 
-        Ok(())
+    async fn get_namespace(&self, namespace: &str) -> anyhow::Result<Option<Namespace>> {
+        let namespaces = self.namespaces();
+        optional(namespaces.get(namespace).await)
+    }
+
+    async fn get_secret(&self, secret: &str) -> anyhow::Result<Option<Secret>> {
+        let secrets = self.secrets();
+        optional(secrets.get(secret).await)
+    }
+
+    async fn get_configmap(&self, configmap: &str) -> anyhow::Result<Option<ConfigMap>> {
+        let configmaps = self.configmaps();
+        optional(configmaps.get(configmap).await)
     }
 
     fn delete_params(&self) -> api::DeleteParams {
@@ -173,6 +224,15 @@ impl Kubectl {
         api::PostParams::default()
     }
 
+    fn patch_params(&self) -> api::PatchParams {
+        api::PatchParams::default()
+    }
+
+    /// Statehub owns the objects it applies, so conflicts are forced through
+    fn apply_params(&self) -> api::PatchParams {
+        api::PatchParams::apply(STATEHUB_FIELD_MANAGER).force()
+    }
+
     fn namespaces(&self) -> Api<Namespace> {
         Api::all(self.client.clone())
     }
@@ -185,6 +245,10 @@ impl Kubectl {
         Api::namespaced(self.client.clone(), &self.namespace)
     }
 
+    fn persistent_volume_claims(&self) -> Api<PersistentVolumeClaim> {
+        Api::all(self.client.clone())
+    }
+
     fn secrets(&self) -> Api<Secret> {
         Api::namespaced(self.client.clone(), &self.namespace)
     }
@@ -192,9 +256,82 @@ impl Kubectl {
     fn configmaps(&self) -> Api<ConfigMap> {
         Api::namespaced(self.client.clone(), &self.namespace)
     }
+
+    fn deployments(&self) -> Api<Deployment> {
+        Api::namespaced(self.client.clone(), &self.namespace)
+    }
+
+    fn daemon_sets(&self) -> Api<DaemonSet> {
+        Api::namespaced(self.client.clone(), &self.namespace)
+    }
+
+    fn stateful_sets(&self) -> Api<StatefulSet> {
+        Api::namespaced(self.client.clone(), &self.namespace)
+    }
+}
+
+/// Treat k8s "404 Not Found" as a missing object rather than an error
+fn optional<T>(result: kube::Result<T>) -> anyhow::Result<Option<T>> {
+    match result {
+        Ok(object) => Ok(Some(object)),
+        Err(kube::Error::Api(err)) if err.code == 404 => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Labels marking objects created and managed by statehub CLI
+fn statehub_labels() -> json::Value {
+    json::json!({
+        "app.kubernetes.io/managed-by": STATEHUB_FIELD_MANAGER,
+        "app.kubernetes.io/part-of": "statehub",
+    })
+}
+
+fn configmap(
+    namespace: &str,
+    name: &str,
+    config: &ClusterAgentConfig,
+) -> anyhow::Result<ConfigMap> {
+    let configmap = json::from_value(json::json!({
+        "apiVersion": "v1",
+        "kind": "ConfigMap",
+        "metadata": {
+            "name": name,
+            "namespace": namespace,
+            "labels": statehub_labels(),
+            "annotations": {
+                STATEHUB_CLI_VERSION_ANNOTATION: env!("CARGO_PKG_VERSION"),
+                STATEHUB_CLUSTER_ANNOTATION: config.cluster_name(),
+            }
+        },
+        "data": config.data(),
+    }))?;
+    Ok(configmap)
+}
+
+fn secret(namespace: &str, r#type: &str, name: &str, secret: &str) -> anyhow::Result<Secret> {
+    let secret = json::from_value(json::json!({
+        "apiVersion": "v1",
+        "kind": "Secret",
+        "metadata": {
+            "name": name,
+            "namespace": namespace,
+            "labels": statehub_labels(),
+            "annotations": {
+                STATEHUB_CLI_VERSION_ANNOTATION: env!("CARGO_PKG_VERSION"),
+                STATEHUB_CLUSTER_TOKEN_ISSUED_ANNOTATION: Utc::now().to_rfc3339(),
+            }
+        },
+        "type": r#type,
+        "data": {
+            "cluster-token": base64::encode(secret),
+        }
+    }))?;
+    Ok(secret)
 }
 
 pub(crate) async fn get_regions(
+    context: Option<&str>,
     zone: bool,
 ) -> anyhow::Result<HashMap<Option<String>, Vec<String>>> {
     let group_nodes = |nodes| {
@@ -205,11 +342,15 @@ pub(crate) async fn get_regions(
         }
     };
 
-    Kubectl::default().await?.all_nodes().await.map(group_nodes)
+    Kubectl::default(context)
+        .await?
+        .all_nodes()
+        .await
+        .map(group_nodes)
 }
 
-pub(crate) async fn collect_node_locations() -> anyhow::Result<Vec<Location>> {
-    get_regions(false)
+pub(crate) async fn collect_node_locations(context: Option<&str>) -> anyhow::Result<Vec<Location>> {
+    get_regions(context, false)
         .await?
         .into_iter()
         .map(|(region, nodes)| {
@@ -222,9 +363,12 @@ pub(crate) async fn collect_node_locations() -> anyhow::Result<Vec<Location>> {
         .map_err(anyhow::Error::msg)
 }
 
-pub(crate) async fn validate_namespace(namespace: impl AsRef<str>) -> anyhow::Result<Namespace> {
+pub(crate) async fn validate_namespace(
+    context: Option<&str>,
+    namespace: impl AsRef<str>,
+) -> anyhow::Result<Namespace> {
     let namespace = namespace.as_ref();
-    let kube = Kubectl::kube_system().await?;
+    let kube = Kubectl::kube_system(context).await?;
     let existing = kube
         .all_namespaces()
         .await?
@@ -241,59 +385,135 @@ pub(crate) async fn validate_namespace(namespace: impl AsRef<str>) -> anyhow::Re
 }
 
 pub(crate) async fn list_namespaces() -> anyhow::Result<impl IntoIterator<Item = Namespace>> {
-    Kubectl::kube_system().await?.all_namespaces().await
+    Kubectl::kube_system(None).await?.all_namespaces().await
 }
 
 pub(crate) async fn list_nodes() -> anyhow::Result<impl IntoIterator<Item = Node>> {
-    Kubectl::default().await?.all_nodes().await
+    Kubectl::default(None).await?.all_nodes().await
 }
 
 pub(crate) async fn list_pods() -> anyhow::Result<impl IntoIterator<Item = Pod>> {
-    Kubectl::kube_system().await?.all_pods().await
+    Kubectl::kube_system(None).await?.all_pods().await
 }
 
 pub(crate) async fn store_configmap(
+    context: Option<&str>,
     namespace: &str,
-    cluster_name: &v0::ClusterName,
-    default_state: &str,
-    api: &str,
+    config: &ClusterAgentConfig,
 ) -> anyhow::Result<ConfigMap> {
-    let kube = Kubectl::with_namespace(namespace).await?;
+    Kubectl::with_namespace(context, namespace)
+        .await?
+        .apply_configmap(STATEHUB_CLUSTER_CONFIGMAP_NAME, config)
+        .await
+}
 
-    if kube
-        .delete_configmap(STATEHUB_CLUSTER_CONFIGMAP_NAME)
+pub(crate) async fn store_cluster_token(
+    context: Option<&str>,
+    namespace: &str,
+    token: &str,
+) -> anyhow::Result<Secret> {
+    Kubectl::with_namespace(context, namespace)
+        .await?
+        .apply_secret(
+            STATEHUB_CLUSTER_TOKEN_SECRET_TYPE,
+            STATEHUB_CLUSTER_TOKEN_SECRET_NAME,
+            token,
+        )
         .await
-        .is_ok()
-    {
-        log::trace!("Removing previous configmap");
-    }
+}
 
-    kube.create_configmap(
-        STATEHUB_CLUSTER_CONFIGMAP_NAME,
-        cluster_name,
-        default_state,
-        api,
-    )
-    .await
+/// Replace the token in place, so there is no window without a token secret
+pub(crate) async fn rotate_cluster_token(
+    context: Option<&str>,
+    namespace: &str,
+    token: &str,
+) -> anyhow::Result<Secret> {
+    if get_cluster_token(context, namespace).await?.is_none() {
+        log::warn!("No cluster token secret found, creating new one");
+    }
+    store_cluster_token(context, namespace, token).await
 }
 
-pub(crate) async fn store_cluster_token(namespace: &str, token: &str) -> anyhow::Result<Secret> {
-    let kube = Kubectl::with_namespace(namespace).await?;
+/// Returns `false` if there was no cluster token secret to delete
+pub(crate) async fn delete_cluster_token(
+    context: Option<&str>,
+    namespace: &str,
+) -> anyhow::Result<bool> {
+    let kube = Kubectl::with_namespace(context, namespace).await?;
 
     if kube
-        .delete_secret(STATEHUB_CLUSTER_TOKEN_SECRET_NAME)
-        .await
-        .is_ok()
+        .get_secret(STATEHUB_CLUSTER_TOKEN_SECRET_NAME)
+        .await?
+        .is_some()
     {
-        log::trace!("Removing previous cluster token");
+        kube.delete_secret(STATEHUB_CLUSTER_TOKEN_SECRET_NAME)
+            .await?;
+        Ok(true)
+    } else {
+        Ok(false)
     }
+}
+
+/// Restart all the deployments, daemonsets and statefulsets in the namespace
+pub(crate) async fn restart_workloads(
+    context: Option<&str>,
+    namespace: &str,
+) -> anyhow::Result<Vec<String>> {
+    let kube = Kubectl::with_namespace(context, namespace).await?;
+    let mut restarted = vec![];
+    restarted.extend(kube.restart_workloads(kube.deployments()).await?);
+    restarted.extend(kube.restart_workloads(kube.daemon_sets()).await?);
+    restarted.extend(kube.restart_workloads(kube.stateful_sets()).await?);
+    Ok(restarted)
+}
+
+pub(crate) async fn get_pods(
+    context: Option<&str>,
+    namespace: &str,
+) -> anyhow::Result<impl IntoIterator<Item = Pod>> {
+    Kubectl::with_namespace(context, namespace)
+        .await?
+        .all_pods()
+        .await
+}
+
+pub(crate) async fn get_persistent_volume_claims(
+    context: Option<&str>,
+) -> anyhow::Result<impl IntoIterator<Item = PersistentVolumeClaim>> {
+    Kubectl::default(context)
+        .await?
+        .all_persistent_volume_claims()
+        .await
+}
+
+pub(crate) async fn get_namespace(
+    context: Option<&str>,
+    namespace: &str,
+) -> anyhow::Result<Option<Namespace>> {
+    Kubectl::kube_system(context)
+        .await?
+        .get_namespace(namespace)
+        .await
+}
 
-    kube.create_secret(
-        STATEHUB_CLUSTER_TOKEN_SECRET_TYPE,
-        STATEHUB_CLUSTER_TOKEN_SECRET_NAME,
-        token,
-    )
-    .await
+pub(crate) async fn get_cluster_token(
+    context: Option<&str>,
+    namespace: &str,
+) -> anyhow::Result<Option<Secret>> {
+    Kubectl::with_namespace(context, namespace)
+        .await?
+        .get_secret(STATEHUB_CLUSTER_TOKEN_SECRET_NAME)
+        .await
+}
+
+pub(crate) async fn get_configmap(
+    context: Option<&str>,
+    namespace: &str,
+) -> anyhow::Result<Option<ConfigMap>> {
+    Kubectl::with_namespace(context, namespace)
+        .await?
+        .get_configmap(STATEHUB_CLUSTER_CONFIGMAP_NAME)
+        .await
 }
 
 pub(crate) fn helm_is_found() -> bool {
@@ -308,19 +528,29 @@ pub(crate) fn extract_cluster_token(secret: &Secret) -> Option<Cow<'_, str>> {
         .map(String::from_utf8_lossy)
 }
 
-// TODO: implement provider fetch out of cluster
+/// When the current cluster token was issued, falls back to the secret creation time
+pub(crate) fn cluster_token_issued(secret: &Secret) -> Option<DateTime<Utc>> {
+    secret
+        .metadata
+        .annotations
+        .get(STATEHUB_CLUSTER_TOKEN_ISSUED_ANNOTATION)
+        .and_then(|issued| DateTime::parse_from_rfc3339(issued).ok())
+        .map(|issued| issued.with_timezone(&Utc))
+        .or_else(|| {
+            secret
+                .metadata
+                .creation_timestamp
+                .as_ref()
+                .map(|time| time.0)
+        })
+}
+
 pub(crate) async fn get_cluster_provider(
-    _cluster: &v0::ClusterName,
-) -> anyhow::Result<v0::Provider> {
-    let kube = Kubectl::default().await?;
-    let nodes = kube.all_nodes().await?;
-    if is_aks(nodes) {
-        Ok(v0::Provider::Aks)
-    } else if is_eks() {
-        Ok(v0::Provider::Eks)
-    } else {
-        Ok(v0::Provider::Generic)
-    }
+    context: Option<&str>,
+) -> anyhow::Result<ProviderDetection> {
+    let kube = Kubectl::default(context).await?;
+    let nodes = kube.all_nodes().await?.into_iter().collect::<Vec<_>>();
+    Ok(detect_provider(&nodes))
 }
 
 pub(crate) fn get_current_cluster_name() -> Option<v0::ClusterName> {
@@ -330,3 +560,53 @@ pub(crate) fn get_current_cluster_name() -> Option<v0::ClusterName> {
         .map(kubeconfig::normalize_name)
         .map(v0::ClusterName::from)
 }
+
+/// Cluster name for the given kubeconfig context, or the default one
+pub(crate) fn get_cluster_name(context: Option<&str>) -> Option<v0::ClusterName> {
+    if let Some(context) = context {
+        Some(kubeconfig::normalize_name(context))
+    } else {
+        get_current_cluster_name()
+    }
+}
+
+/// Find kubeconfig context the given cluster was registered from
+pub(crate) fn find_context(cluster: &v0::ClusterName) -> Option<String> {
+    Kubeconfig::read()
+        .ok()?
+        .all_contexts()
+        .into_iter()
+        .find(|context| kubeconfig::normalize_name(context) == *cluster)
+        .map(String::from)
+}
+
+/// Validate requested kubeconfig contexts, or expand them to every known context.
+/// Empty result means current default context should be used.
+pub(crate) fn resolve_contexts(
+    contexts: Vec<String>,
+    all_contexts: bool,
+) -> anyhow::Result<Vec<String>> {
+    if contexts.is_empty() && !all_contexts {
+        return Ok(contexts);
+    }
+
+    let kubeconfig = Kubeconfig::read()?;
+    if all_contexts {
+        let contexts = kubeconfig
+            .all_contexts()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        anyhow::ensure!(!contexts.is_empty(), "No contexts found in kubeconfig");
+        Ok(contexts)
+    } else {
+        for context in &contexts {
+            anyhow::ensure!(
+                kubeconfig.contains(context),
+                "No such kubeconfig context: '{}'",
+                context
+            );
+        }
+        Ok(contexts)
+    }
+}
@@ -0,0 +1,248 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use k8s_openapi::api::storage::v1::StorageClass;
+
+use super::*;
+
+const DEFAULT_CLASS_ANNOTATION: &str = "storageclass.kubernetes.io/is-default-class";
+const FS_TYPE_PARAMETER: &str = "csi.storage.k8s.io/fstype";
+const STATE_PARAMETER: &str = "state";
+
+impl Kubectl {
+    async fn get_storage_class(&self, name: &str) -> anyhow::Result<Option<StorageClass>> {
+        let storage_classes = self.storage_classes();
+        optional(storage_classes.get(name).await)
+    }
+
+    async fn all_storage_classes(&self) -> anyhow::Result<Vec<StorageClass>> {
+        let storage_classes = self.storage_classes();
+        let lp = self.list_params();
+        Ok(storage_classes.list(&lp).await?.items)
+    }
+
+    async fn apply_storage_class(
+        &self,
+        storage_class: &StorageClass,
+    ) -> anyhow::Result<StorageClass> {
+        let storage_classes = self.storage_classes();
+        let pp = self.apply_params();
+        let storage_class = storage_classes
+            .patch(
+                &storage_class.name(),
+                &pp,
+                &api::Patch::Apply(storage_class),
+            )
+            .await?;
+        Ok(storage_class)
+    }
+
+    async fn delete_storage_class(&self, name: &str) -> anyhow::Result<()> {
+        log::info!("Deleting storage class {}", name);
+        let storage_classes = self.storage_classes();
+        let dp = self.delete_params();
+        storage_classes.delete(name, &dp).await?;
+        Ok(())
+    }
+
+    async fn annotate_default_storage_class(
+        &self,
+        name: &str,
+        default: bool,
+    ) -> anyhow::Result<StorageClass> {
+        let storage_classes = self.storage_classes();
+        let pp = self.patch_params();
+        let patch = json::json!({
+            "metadata": {
+                "annotations": {
+                    DEFAULT_CLASS_ANNOTATION: default.to_string(),
+                }
+            }
+        });
+        let storage_class = storage_classes
+            .patch(name, &pp, &api::Patch::Merge(&patch))
+            .await?;
+        Ok(storage_class)
+    }
+
+    fn storage_classes(&self) -> Api<StorageClass> {
+        Api::all(self.client.clone())
+    }
+}
+
+/// Whether the storage class applied for a state was new, changed in place, or replaced
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum StorageClassChange {
+    Created,
+    Updated,
+    Replaced,
+}
+
+impl fmt::Display for StorageClassChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Replaced => "replaced",
+        };
+        text.fmt(f)
+    }
+}
+
+/// Create or update k8s storage class backed by the state.
+/// Provisioner parameters of the storage class are immutable,
+/// so if they differ the storage class is deleted and created anew.
+/// Storage class not managed by statehub is only replaced when forced.
+pub(crate) async fn apply_storage_class(
+    context: Option<&str>,
+    state: &v0::StateName,
+    storage_class: &v0::StorageClass,
+    force: bool,
+) -> anyhow::Result<StorageClassChange> {
+    let kube = Kubectl::default(context).await?;
+    let desired = self::storage_class(state, storage_class)?;
+
+    let change = match kube.get_storage_class(&storage_class.name).await? {
+        Some(current) if is_same_provisioning(&current, &desired) => StorageClassChange::Updated,
+        Some(current) => {
+            anyhow::ensure!(
+                force || is_statehub_managed(&current),
+                "Storage class {} exists and is not managed by statehub, use --force to replace it",
+                storage_class.name
+            );
+            kube.delete_storage_class(&storage_class.name).await?;
+            StorageClassChange::Replaced
+        }
+        None => StorageClassChange::Created,
+    };
+
+    kube.apply_storage_class(&desired).await?;
+    Ok(change)
+}
+
+/// Make the given storage class the only cluster default one,
+/// or clear the default annotation from all the storage classes if `None`.
+/// Returns the names of storage classes that changed.
+pub(crate) async fn set_default_storage_class(
+    context: Option<&str>,
+    name: Option<&str>,
+) -> anyhow::Result<Vec<String>> {
+    let kube = Kubectl::default(context).await?;
+    let storage_classes = kube.all_storage_classes().await?;
+
+    if let Some(name) = name {
+        anyhow::ensure!(
+            storage_classes.iter().any(|sc| sc.name() == name),
+            "Storage class {} does not exist in the cluster",
+            name
+        );
+    }
+
+    let mut changed = vec![];
+    for storage_class in storage_classes {
+        let current = storage_class.name();
+        let default = name == Some(current.as_str());
+        if is_default(&storage_class) != default {
+            kube.annotate_default_storage_class(&current, default)
+                .await?;
+            changed.push(current);
+        }
+    }
+
+    Ok(changed)
+}
+
+fn is_default(storage_class: &StorageClass) -> bool {
+    let annotation = storage_class
+        .metadata
+        .annotations
+        .get(DEFAULT_CLASS_ANNOTATION);
+    matches!(annotation.map(String::as_str), Some("true"))
+}
+
+fn is_statehub_managed(storage_class: &StorageClass) -> bool {
+    let managed_by = storage_class
+        .metadata
+        .labels
+        .get("app.kubernetes.io/managed-by");
+    matches!(managed_by.map(String::as_str), Some(STATEHUB_FIELD_MANAGER))
+        || storage_class.provisioner == STATEHUB_CSI_DRIVER
+}
+
+fn is_same_provisioning(current: &StorageClass, desired: &StorageClass) -> bool {
+    current.provisioner == desired.provisioner
+        && current.parameters == desired.parameters
+        && current.volume_binding_mode == desired.volume_binding_mode
+        && current.reclaim_policy == desired.reclaim_policy
+}
+
+fn storage_class(
+    state: &v0::StateName,
+    storage_class: &v0::StorageClass,
+) -> anyhow::Result<StorageClass> {
+    let mount_options = storage_class
+        .mount_options
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .collect::<Vec<_>>();
+    let storage_class = json::from_value(json::json!({
+        "apiVersion": "storage.k8s.io/v1",
+        "kind": "StorageClass",
+        "metadata": {
+            "name": storage_class.name,
+            "labels": statehub_labels(),
+            "annotations": {
+                STATEHUB_CLI_VERSION_ANNOTATION: env!("CARGO_PKG_VERSION"),
+                STATEHUB_STATE_ANNOTATION: state,
+            }
+        },
+        "provisioner": STATEHUB_CSI_DRIVER,
+        "parameters": {
+            STATE_PARAMETER: state,
+            FS_TYPE_PARAMETER: storage_class.fs_type,
+        },
+        "reclaimPolicy": "Delete",
+        "volumeBindingMode": storage_class.volume_binding_mode.to_string(),
+        "mountOptions": mount_options,
+    }))?;
+    Ok(storage_class)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_class() {
+        let state = v0::StateName::from("alfa");
+        let mut class = v0::StorageClass::new("alfa-sc");
+        class.mount_options = Some(String::from("noatime, nodiratime"));
+        let sc = super::storage_class(&state, &class).unwrap();
+        assert_eq!(sc.name(), "alfa-sc");
+        assert_eq!(sc.provisioner, STATEHUB_CSI_DRIVER);
+        assert_eq!(sc.parameters[STATE_PARAMETER], "alfa");
+        assert_eq!(sc.parameters[FS_TYPE_PARAMETER], "ext4");
+        assert_eq!(
+            sc.volume_binding_mode.as_deref(),
+            Some("WaitForFirstConsumer")
+        );
+        assert_eq!(sc.mount_options, ["noatime", "nodiratime"]);
+        assert!(!is_default(&sc));
+        assert!(is_statehub_managed(&sc));
+    }
+
+    #[test]
+    fn foreign_storage_class() {
+        let sc = json::from_value::<StorageClass>(json::json!({
+            "metadata": { "name": "standard" },
+            "provisioner": "kubernetes.io/aws-ebs",
+        }))
+        .unwrap();
+        assert!(!is_statehub_managed(&sc));
+    }
+}
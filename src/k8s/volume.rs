@@ -0,0 +1,314 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use k8s_openapi::api::core::v1::PersistentVolume;
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Show;
+
+use super::*;
+
+const STATE_ATTRIBUTE: &str = "state";
+
+impl Kubectl {
+    async fn all_persistent_volumes(&self) -> anyhow::Result<Vec<PersistentVolume>> {
+        let pvs = self.persistent_volumes();
+        let lp = self.list_params();
+        Ok(pvs.list(&lp).await?.items)
+    }
+
+    async fn resize_persistent_volume_claim(
+        &self,
+        namespace: &str,
+        name: &str,
+        size: &v0::VolumeSize,
+    ) -> anyhow::Result<PersistentVolumeClaim> {
+        let pvcs = Api::<PersistentVolumeClaim>::namespaced(self.client.clone(), namespace);
+        let pp = self.patch_params();
+        let patch = json::json!({
+            "spec": {
+                "resources": {
+                    "requests": {
+                        "storage": size.to_string(),
+                    }
+                }
+            }
+        });
+        let pvc = pvcs.patch(name, &pp, &api::Patch::Merge(&patch)).await?;
+        Ok(pvc)
+    }
+
+    async fn pods_in_all_namespaces(&self) -> anyhow::Result<Vec<Pod>> {
+        let pods = Api::<Pod>::all(self.client.clone());
+        let lp = self.list_params();
+        Ok(pods.list(&lp).await?.items)
+    }
+
+    fn persistent_volumes(&self) -> Api<PersistentVolume> {
+        Api::all(self.client.clone())
+    }
+}
+
+/// Persistent volume claim bound to a statehub volume
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct VolumeClaim {
+    pub(crate) namespace: String,
+    pub(crate) name: String,
+    pub(crate) persistent_volume: String,
+}
+
+impl fmt::Display for VolumeClaim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.namespace, self.name)
+    }
+}
+
+/// Pod (if any) using statehub volume through the persistent volume claim
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VolumeConsumer {
+    pub(crate) volume: v0::VolumeName,
+    pub(crate) namespace: String,
+    pub(crate) persistent_volume_claim: String,
+    pub(crate) persistent_volume: String,
+    pub(crate) pod: Option<String>,
+    pub(crate) node: Option<String>,
+}
+
+impl Show for VolumeConsumer {
+    fn show(&self) -> String {
+        let none = || String::from("-");
+        format!(
+            "{}/{} (pv {}) pod: {} node: {}",
+            self.namespace,
+            self.persistent_volume_claim,
+            self.persistent_volume,
+            self.pod.clone().unwrap_or_else(none),
+            self.node.clone().unwrap_or_else(none),
+        )
+    }
+}
+
+impl Show for Vec<VolumeConsumer> {
+    fn show(&self) -> String {
+        self.iter().map(Show::show).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Claims bound to persistent volumes provisioned by statehub CSI driver for the volume
+pub(crate) async fn get_volume_claims(
+    context: Option<&str>,
+    state: &v0::StateName,
+    volume: &v0::Volume,
+) -> anyhow::Result<Vec<VolumeClaim>> {
+    let kube = Kubectl::default(context).await?;
+    let pvs = kube.all_persistent_volumes().await?;
+    Ok(volume_claims(&pvs, state, volume))
+}
+
+/// Everything in the cluster that uses any of the volumes.
+/// Claims not used by any pod are reported with no pod.
+pub(crate) async fn get_volume_consumers(
+    context: Option<&str>,
+    state: &v0::StateName,
+    volumes: &[v0::Volume],
+) -> anyhow::Result<Vec<VolumeConsumer>> {
+    let kube = Kubectl::default(context).await?;
+    let pvs = kube.all_persistent_volumes().await?;
+    let pods = kube.pods_in_all_namespaces().await?;
+
+    let mut consumers = vec![];
+    for volume in volumes {
+        for claim in volume_claims(&pvs, state, volume) {
+            let consumer = |pod: Option<&Pod>| VolumeConsumer {
+                volume: volume.name.clone(),
+                namespace: claim.namespace.clone(),
+                persistent_volume_claim: claim.name.clone(),
+                persistent_volume: claim.persistent_volume.clone(),
+                pod: pod.map(|pod| pod.name()),
+                node: pod
+                    .and_then(|pod| pod.spec.as_ref())
+                    .and_then(|spec| spec.node_name.clone()),
+            };
+            let users = pods
+                .iter()
+                .filter(|pod| is_claim_user(pod, &claim))
+                .map(|pod| consumer(Some(pod)))
+                .collect::<Vec<_>>();
+            if users.is_empty() {
+                consumers.push(consumer(None));
+            } else {
+                consumers.extend(users);
+            }
+        }
+    }
+
+    Ok(consumers)
+}
+
+pub(crate) async fn resize_volume_claim(
+    context: Option<&str>,
+    claim: &VolumeClaim,
+    size: &v0::VolumeSize,
+) -> anyhow::Result<()> {
+    Kubectl::default(context)
+        .await?
+        .resize_persistent_volume_claim(&claim.namespace, &claim.name, size)
+        .await?;
+    Ok(())
+}
+
+fn volume_claims(
+    pvs: &[PersistentVolume],
+    state: &v0::StateName,
+    volume: &v0::Volume,
+) -> Vec<VolumeClaim> {
+    pvs.iter()
+        .filter(|pv| is_statehub_volume(pv, state, volume))
+        .filter_map(|pv| {
+            let claim = pv.spec.as_ref()?.claim_ref.as_ref()?;
+            Some(VolumeClaim {
+                namespace: claim.namespace.clone()?,
+                name: claim.name.clone()?,
+                persistent_volume: pv.name(),
+            })
+        })
+        .collect()
+}
+
+fn is_claim_user(pod: &Pod, claim: &VolumeClaim) -> bool {
+    pod.namespace().as_deref() == Some(claim.namespace.as_str())
+        && pod
+            .spec
+            .iter()
+            .flat_map(|spec| spec.volumes.iter())
+            .filter_map(|volume| volume.persistent_volume_claim.as_ref())
+            .any(|pvc| pvc.claim_name == claim.name)
+}
+
+/// CSI volume handle refers to the volume by id, by `state/volume`,
+/// or by bare name when the state attribute says it is the same state
+fn is_statehub_volume(pv: &PersistentVolume, state: &v0::StateName, volume: &v0::Volume) -> bool {
+    let csi = match pv.spec.as_ref().and_then(|spec| spec.csi.as_ref()) {
+        Some(csi) if csi.driver == STATEHUB_CSI_DRIVER => csi,
+        _ => return false,
+    };
+
+    let handle = csi.volume_handle.as_str();
+    let qualified =
+        volume.id.to_string() == handle || handle == format!("{}/{}", state, volume.name);
+
+    match csi.volume_attributes.get(STATE_ATTRIBUTE) {
+        Some(attribute) => *state == attribute.as_str() && (qualified || volume.name == handle),
+        None => qualified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v0::fixtures;
+
+    use super::*;
+
+    fn volume() -> v0::Volume {
+        let mut volume = fixtures::volume("data", None, &[]);
+        volume.id = "6f1c2d8e-3b7a-4c1e-9f0a-2b5d8c7e1a94".parse().unwrap();
+        volume
+    }
+
+    fn pv(driver: &str, handle: &str, state: &str) -> PersistentVolume {
+        json::from_value(json::json!({
+            "metadata": { "name": "pvc-1234" },
+            "spec": {
+                "csi": {
+                    "driver": driver,
+                    "volumeHandle": handle,
+                    "volumeAttributes": { STATE_ATTRIBUTE: state }
+                },
+                "claimRef": { "namespace": "db", "name": "postgres-data" }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn statehub_volume() {
+        let state = v0::StateName::from("alfa");
+        let volume = volume();
+        assert!(is_statehub_volume(
+            &pv(STATEHUB_CSI_DRIVER, "data", "alfa"),
+            &state,
+            &volume
+        ));
+        assert!(is_statehub_volume(
+            &pv(
+                STATEHUB_CSI_DRIVER,
+                "6f1c2d8e-3b7a-4c1e-9f0a-2b5d8c7e1a94",
+                "alfa"
+            ),
+            &state,
+            &volume
+        ));
+        assert!(!is_statehub_volume(
+            &pv(STATEHUB_CSI_DRIVER, "data", "bravo"),
+            &state,
+            &volume
+        ));
+        assert!(!is_statehub_volume(
+            &pv("ebs.csi.aws.com", "data", "alfa"),
+            &state,
+            &volume
+        ));
+    }
+
+    #[test]
+    fn statehub_volume_without_state() {
+        let state = v0::StateName::from("alfa");
+        let volume = volume();
+        let stateless = |handle: &str| {
+            let mut pv = pv(STATEHUB_CSI_DRIVER, handle, "alfa");
+            if let Some(csi) = pv.spec.as_mut().and_then(|spec| spec.csi.as_mut()) {
+                csi.volume_attributes.clear();
+            }
+            pv
+        };
+        assert!(!is_statehub_volume(&stateless("data"), &state, &volume));
+        assert!(is_statehub_volume(&stateless("alfa/data"), &state, &volume));
+        assert!(!is_statehub_volume(
+            &stateless("bravo/data"),
+            &state,
+            &volume
+        ));
+        assert!(is_statehub_volume(
+            &stateless("6f1c2d8e-3b7a-4c1e-9f0a-2b5d8c7e1a94"),
+            &state,
+            &volume
+        ));
+    }
+
+    #[test]
+    fn claim_user() {
+        let state = v0::StateName::from("alfa");
+        let pvs = [pv(STATEHUB_CSI_DRIVER, "data", "alfa")];
+        let claims = volume_claims(&pvs, &state, &volume());
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].to_string(), "db/postgres-data");
+
+        let pod = |namespace: &str| -> Pod {
+            json::from_value(json::json!({
+                "metadata": { "name": "postgres-0", "namespace": namespace },
+                "spec": {
+                    "containers": [],
+                    "volumes": [
+                        { "name": "data", "persistentVolumeClaim": { "claimName": "postgres-data" } }
+                    ]
+                }
+            }))
+            .unwrap()
+        };
+        assert!(is_claim_user(&pod("db"), &claims[0]));
+        assert!(!is_claim_user(&pod("default"), &claims[0]));
+    }
+}
@@ -9,7 +9,8 @@ use std::str::FromStr;
 use itertools::Itertools;
 use thiserror::Error;
 
-use crate::traits::Show;
+use crate::suggest;
+use crate::traits::{CloudRegion, Show};
 use crate::v0;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -28,6 +29,49 @@ impl fmt::Display for Location {
     }
 }
 
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+impl Location {
+    /// Every location statehub can be deployed to
+    pub(crate) fn all() -> Vec<Self> {
+        let aws = v0::AwsRegion::ALL.iter().copied().map(Self::Aws);
+        let azure = v0::AzureRegion::ALL.iter().copied().map(Self::Azure);
+        aws.chain(azure).collect()
+    }
+
+    pub(crate) fn display_name(&self) -> &'static str {
+        match self {
+            Self::Aws(region) => region.display_name(),
+            Self::Azure(region) => region.display_name(),
+        }
+    }
+
+    pub(crate) fn is_same_vendor(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Aws(_), Self::Aws(_)) | (Self::Azure(_), Self::Azure(_))
+        )
+    }
+
+    /// Great circle distance between the locations
+    pub(crate) fn distance_km(&self, other: &Self) -> f64 {
+        let (lat1, lon1) = self.coordinates();
+        let (lat2, lon2) = other.coordinates();
+        let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (lon2 - lon1).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+
+    fn coordinates(&self) -> (f64, f64) {
+        match self {
+            Self::Aws(region) => region.coordinates(),
+            Self::Azure(region) => region.coordinates(),
+        }
+    }
+}
+
 impl From<v0::AwsRegion> for Location {
     fn from(region: v0::AwsRegion) -> Self {
         Self::Aws(region)
@@ -56,13 +100,32 @@ impl FromStr for Location {
                 aws, azure
             )),
             (Err(aws), Err(azure)) => {
-                let error = format!("{} or {}", aws, azure);
+                let regions = region_names(text.contains('/'));
+                let hint = suggest::did_you_mean(text, regions.iter().map(String::as_str))
+                    .unwrap_or_else(|| {
+                        String::from("run 'statehub regions' to list supported regions")
+                    });
+                let error = format!("{} or {}, {}", aws, azure, hint);
                 Err(error)
             }
         }
     }
 }
 
+/// All known regions, with vendor prefix or without it
+fn region_names(prefixed: bool) -> Vec<String> {
+    Location::all()
+        .iter()
+        .map(|location| {
+            if prefixed {
+                format!("{:#}", location)
+            } else {
+                location.to_string()
+            }
+        })
+        .collect()
+}
+
 impl Show for Location {
     fn show(&self) -> String {
         format!("{:#}", self)
@@ -32,6 +32,8 @@ mod output;
 mod show;
 // Main tool bussines logic
 mod statehub;
+// "did you mean" suggestions
+mod suggest;
 // custom traits
 mod traits;
 // stathub API v0 definitions
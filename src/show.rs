@@ -46,6 +46,14 @@ impl Show for HashMap<String, Vec<String>> {
     }
 }
 
+impl Show for HashMap<String, HashMap<String, Vec<String>>> {
+    fn show(&self) -> String {
+        self.iter()
+            .map(|(context, regions)| format!("{}:\n{}", context, regions.show()))
+            .join("\n")
+    }
+}
+
 impl Show for Command {
     fn show(&self) -> String {
         format!("{:?}", self).replace("\"", "")
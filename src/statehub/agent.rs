@@ -0,0 +1,66 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use super::*;
+
+impl StateHub {
+    pub(super) async fn get_cluster_config(
+        &self,
+        name: v0::ClusterName,
+        key: Option<String>,
+        context: Option<String>,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let context = self.cluster_context_helper(&name, context)?;
+        let config = self
+            .cluster_config_helper(context.as_deref(), &namespace)
+            .await?;
+
+        if let Some(key) = key {
+            let value = config
+                .get(&key)
+                .ok_or_else(|| anyhow::anyhow!("Setting {} is not set", key))?;
+            Output::from(value).print(&self.stdout, self.json)
+        } else {
+            Output::from(config).print(&self.stdout, self.json)
+        }
+    }
+
+    pub(super) async fn set_cluster_config(
+        &self,
+        name: v0::ClusterName,
+        settings: Vec<k8s::AgentSetting>,
+        context: Option<String>,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let context = self.cluster_context_helper(&name, context)?;
+        let context = context.as_deref();
+        let mut config = self.cluster_config_helper(context, &namespace).await?;
+
+        for setting in settings {
+            config.set(setting);
+        }
+        k8s::store_configmap(context, &namespace, &config).await?;
+        self.verbosely("Statehub components pick up configmap changes on restart")?;
+
+        Output::from(config).print(&self.stdout, self.json)
+    }
+
+    async fn cluster_config_helper(
+        &self,
+        context: Option<&str>,
+        namespace: &str,
+    ) -> anyhow::Result<k8s::ClusterAgentConfig> {
+        let configmap = k8s::get_configmap(context, namespace)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No statehub configmap found in namespace {}, run 'statehub register-cluster' first",
+                    namespace
+                )
+            })?;
+        k8s::ClusterAgentConfig::from_data(&configmap.data)
+    }
+}
@@ -69,13 +69,35 @@ impl Config {
     }
 
     pub(crate) fn load() -> anyhow::Result<Self> {
+        Self::read().map(Self::rolling_load)
+    }
+
+    /// Check config file on disk and return its version
+    pub(crate) fn validate() -> anyhow::Result<&'static str> {
+        let config = Self::read()?;
+        if ConfigV1::validate_config(&config).is_ok() {
+            Ok(ConfigV1::VERSION)
+        } else if ConfigV2::validate_config(&config).is_ok() {
+            Ok(ConfigV2::VERSION)
+        } else {
+            anyhow::bail!("Unsupported config file version")
+        }
+    }
+
+    pub(crate) fn is_current(version: &str) -> bool {
+        version == Self::VERSION
+    }
+
+    pub(crate) fn path() -> anyhow::Result<PathBuf> {
+        Self::config_file()
+    }
+
+    fn read() -> anyhow::Result<toml::Value> {
         let path = Self::config_file()?;
         anyhow::ensure!(path.exists(), "Config file does not exist");
-        let config = fs::read_to_string(path)
+        fs::read_to_string(path)
             .context("Reading config file")
-            .and_then(|text| toml::from_str::<toml::Value>(&text).context("Parsing config file"))?;
-
-        Ok(Self::rolling_load(config))
+            .and_then(|text| toml::from_str::<toml::Value>(&text).context("Parsing config file"))
     }
 
     fn rolling_load(config: toml::Value) -> Self {
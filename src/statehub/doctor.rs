@@ -0,0 +1,349 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use console::Emoji;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct Check {
+    check: String,
+    status: CheckStatus,
+    message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct Checklist(Vec<Check>);
+
+impl StateHub {
+    pub(super) async fn doctor(
+        &self,
+        context: Option<String>,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let mut checks = Checklist::default();
+        let context = context.as_deref();
+
+        self.check_config(&mut checks);
+        let api = self.check_api(&mut checks).await;
+        let name = self.check_kubeconfig(&mut checks, context);
+        let cluster = if let (true, Some(name)) = (api, &name) {
+            self.check_registration(&mut checks, name).await
+        } else {
+            None
+        };
+        let kube = self.check_node_locations(&mut checks, context).await;
+        let helm = self.check_helm(&mut checks);
+
+        if kube {
+            self.check_cluster_objects(&mut checks, context, &namespace, name.as_ref())
+                .await;
+        }
+
+        if let (true, true, Some(cluster)) = (kube, helm, &cluster) {
+            let helm =
+                k8s::Helm::new(namespace, None, false).with_context(context.map(String::from));
+            self.check_helm_release(&mut checks, &helm, cluster).await;
+        }
+
+        let failed = checks.has_failures();
+        Output::from(checks).print(&self.stdout, self.json)?;
+        anyhow::ensure!(!failed, "Some checks failed");
+        Ok(())
+    }
+
+    fn check_config(&self, checks: &mut Checklist) {
+        const CHECK: &str = "config";
+        let path = Config::path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        match Config::validate() {
+            Ok(version) if Config::is_current(version) => {
+                checks.pass(CHECK, format_args!("{} (version {})", path, version))
+            }
+            Ok(version) => checks.warn(
+                CHECK,
+                format_args!(
+                    "{} uses outdated version {}, run 'statehub save-config' to upgrade",
+                    path, version
+                ),
+            ),
+            Err(err) => checks.warn(CHECK, format_args!("{}: {}, using defaults", path, err)),
+        }
+    }
+
+    async fn check_api(&self, checks: &mut Checklist) -> bool {
+        const CHECK: &str = "api";
+        let url = self.api.url("");
+        match self.api.ping().await {
+            Ok(_) if self.api.has_token() => {
+                checks.pass(CHECK, format_args!("{} is reachable, token accepted", url));
+                true
+            }
+            Ok(_) => {
+                checks.warn(
+                    CHECK,
+                    format_args!("{} is reachable, but no token configured", url),
+                );
+                false
+            }
+            Err(err) if api::is_unauthorized(&err) => {
+                checks.fail(
+                    CHECK,
+                    format_args!("{} rejected the token, run 'statehub login'", url),
+                );
+                false
+            }
+            Err(err) => {
+                checks.fail(CHECK, format_args!("{} is not reachable: {}", url, err));
+                false
+            }
+        }
+    }
+
+    fn check_kubeconfig(
+        &self,
+        checks: &mut Checklist,
+        context: Option<&str>,
+    ) -> Option<v0::ClusterName> {
+        const CHECK: &str = "kubeconfig";
+        let resolved = context
+            .map(|context| k8s::resolve_contexts(vec![context.to_string()], false))
+            .transpose();
+        match (resolved, k8s::get_cluster_name(context)) {
+            (Err(err), _) => {
+                checks.fail(CHECK, err);
+                None
+            }
+            (Ok(_), Some(name)) => {
+                let context = context.unwrap_or("default context");
+                checks.pass(
+                    CHECK,
+                    format_args!("{} resolves to cluster {}", context, name),
+                );
+                Some(name)
+            }
+            (Ok(_), None) => {
+                checks.fail(CHECK, "No default Kubernetes context found");
+                None
+            }
+        }
+    }
+
+    async fn check_registration(
+        &self,
+        checks: &mut Checklist,
+        name: &v0::ClusterName,
+    ) -> Option<v0::Cluster> {
+        const CHECK: &str = "registration";
+        match self.api.get_cluster(name).await {
+            Ok(cluster) => {
+                checks.pass(CHECK, format_args!("Cluster {} is registered", name));
+                Some(cluster.into_inner())
+            }
+            Err(err) => {
+                checks.warn(
+                    CHECK,
+                    format_args!("Cluster {} is not registered: {}", name, err),
+                );
+                None
+            }
+        }
+    }
+
+    async fn check_node_locations(&self, checks: &mut Checklist, context: Option<&str>) -> bool {
+        const CHECK: &str = "node regions";
+        let regions = match k8s::get_regions(context, false).await {
+            Ok(regions) => regions,
+            Err(err) => {
+                checks.fail(CHECK, format_args!("Cannot list nodes: {}", err));
+                return false;
+            }
+        };
+
+        let mut unlabeled = vec![];
+        let mut unsupported = vec![];
+        let mut locations = vec![];
+        for (region, nodes) in regions {
+            match region.map(|region| region.parse::<Location>()) {
+                Some(Ok(location)) => locations.push(location),
+                Some(Err(err)) => unsupported.push(err),
+                None => unlabeled.extend(nodes),
+            }
+        }
+
+        if !unlabeled.is_empty() {
+            checks.fail(
+                CHECK,
+                format_args!(
+                    "Nodes without 'topology.kubernetes.io/region' label: {}",
+                    unlabeled.join(", ")
+                ),
+            );
+        } else if !unsupported.is_empty() {
+            checks.fail(CHECK, unsupported.join(", "));
+        } else {
+            checks.pass(CHECK, locations.show());
+        }
+
+        true
+    }
+
+    fn check_helm(&self, checks: &mut Checklist) -> bool {
+        const CHECK: &str = "helm";
+        if k8s::helm_is_found() {
+            checks.pass(CHECK, "helm is installed");
+            true
+        } else {
+            checks.warn(CHECK, "helm is not found in PATH");
+            false
+        }
+    }
+
+    async fn check_cluster_objects(
+        &self,
+        checks: &mut Checklist,
+        context: Option<&str>,
+        namespace: &str,
+        cluster: Option<&v0::ClusterName>,
+    ) {
+        match k8s::get_namespace(context, namespace).await {
+            Ok(Some(_)) => checks.pass("namespace", format_args!("{} exists", namespace)),
+            Ok(None) => {
+                checks.fail("namespace", format_args!("{} does not exist", namespace));
+                return;
+            }
+            Err(err) => {
+                checks.fail("namespace", err);
+                return;
+            }
+        }
+
+        match k8s::get_cluster_token(context, namespace).await {
+            Ok(Some(secret)) if k8s::extract_cluster_token(&secret).is_some() => {
+                checks.pass("token secret", "Cluster token secret exists")
+            }
+            Ok(Some(_)) => checks.fail("token secret", "Cluster token secret has no token"),
+            Ok(None) => checks.fail("token secret", "Cluster token secret does not exist"),
+            Err(err) => checks.fail("token secret", err),
+        }
+
+        match k8s::get_configmap(context, namespace).await {
+            Ok(Some(configmap)) => {
+                let data = |key| configmap.data.get(key).map(String::as_str);
+                let api = self.api.url("");
+                if cluster.is_some() && data("cluster-name") != cluster.map(|name| name.as_ref()) {
+                    checks.fail(
+                        "configmap",
+                        format_args!(
+                            "cluster-name is {:?}, expected {:?}",
+                            data("cluster-name").unwrap_or_default(),
+                            cluster.map(ToString::to_string).unwrap_or_default()
+                        ),
+                    )
+                } else if data("api-url") != Some(api.as_str()) {
+                    checks.warn(
+                        "configmap",
+                        format_args!(
+                            "api-url is {:?}, expected {:?}",
+                            data("api-url").unwrap_or_default(),
+                            api
+                        ),
+                    )
+                } else {
+                    checks.pass("configmap", "Statehub configmap matches the cluster")
+                }
+            }
+            Ok(None) => checks.fail("configmap", "Statehub configmap does not exist"),
+            Err(err) => checks.fail("configmap", err),
+        }
+    }
+
+    async fn check_helm_release(
+        &self,
+        checks: &mut Checklist,
+        helm: &k8s::Helm,
+        cluster: &v0::Cluster,
+    ) {
+        const CHECK: &str = "helm release";
+        for chart in &cluster.helm {
+            match helm.release(chart).await {
+                Ok(Some(release)) if release.status == "deployed" => {
+                    checks.pass(CHECK, format_args!("{} is {}", chart.chart, release.status))
+                }
+                Ok(Some(release)) => {
+                    checks.warn(CHECK, format_args!("{} is {}", chart.chart, release.status))
+                }
+                Ok(None) => checks.fail(CHECK, format_args!("{} is not installed", chart.chart)),
+                Err(err) => checks.warn(CHECK, format_args!("{}: {}", chart.chart, err)),
+            }
+        }
+    }
+}
+
+impl Checklist {
+    fn add(&mut self, check: &str, status: CheckStatus, message: impl fmt::Display) {
+        let check = check.to_string();
+        let message = message.to_string();
+        self.0.push(Check {
+            check,
+            status,
+            message,
+        });
+    }
+
+    fn pass(&mut self, check: &str, message: impl fmt::Display) {
+        self.add(check, CheckStatus::Pass, message)
+    }
+
+    fn warn(&mut self, check: &str, message: impl fmt::Display) {
+        self.add(check, CheckStatus::Warn, message)
+    }
+
+    fn fail(&mut self, check: &str, message: impl fmt::Display) {
+        self.add(check, CheckStatus::Fail, message)
+    }
+
+    fn has_failures(&self) -> bool {
+        self.0.iter().any(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+impl CheckStatus {
+    const PASS: Emoji<'static, 'static> = Emoji("✅", "[v]");
+    const WARN: Emoji<'static, 'static> = Emoji("⚠️ ", "[!]");
+    const FAIL: Emoji<'static, 'static> = Emoji("❌", "[x]");
+}
+
+impl Show for CheckStatus {
+    fn show(&self) -> String {
+        let status = match self {
+            Self::Pass => Self::PASS,
+            Self::Warn => Self::WARN,
+            Self::Fail => Self::FAIL,
+        };
+        status.to_string()
+    }
+}
+
+impl Show for Check {
+    fn show(&self) -> String {
+        format!("{} {:<14} {}", self.status.show(), self.check, self.message)
+    }
+}
+
+impl Show for Checklist {
+    fn show(&self) -> String {
+        self.0.iter().map(Show::show).join("\n")
+    }
+}
@@ -0,0 +1,211 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::time::Duration;
+
+use tokio::time;
+
+use super::*;
+
+impl StateHub {
+    pub(super) async fn failover(
+        &self,
+        state: v0::StateName,
+        volumes: Vec<v0::VolumeName>,
+        to: Location,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let current = self.api.get_state(&state).await?.into_inner();
+        let volumes = if volumes.is_empty() {
+            self.api.get_all_volumes(state.clone()).await?.into_inner()
+        } else {
+            let volumes = volumes
+                .iter()
+                .map(|volume| self.api.get_volume(&state, volume));
+            future::try_join_all(volumes)
+                .await?
+                .into_iter()
+                .map(Output::into_inner)
+                .collect()
+        };
+        anyhow::ensure!(!volumes.is_empty(), "State {} has no volumes", state);
+
+        ensure_failover_is_safe(&current, &volumes, &to, force)?;
+        let failed_over = self.failover_helper(&state, volumes, to).await?;
+        Output::from(failed_over).print(&self.stdout, self.json)
+    }
+
+    /// Switch primary location of every volume and wait until it takes effect
+    pub(super) async fn failover_helper(
+        &self,
+        state: &v0::StateName,
+        volumes: Vec<v0::Volume>,
+        to: Location,
+    ) -> anyhow::Result<Vec<v0::Volume>> {
+        let mut failed_over = vec![];
+        for volume in volumes {
+            if volume.primary_location() == Some(to) {
+                self.verbosely(format_args!(
+                    "Volume {} is already primary in {}",
+                    volume.name,
+                    to.show()
+                ))?;
+                failed_over.push(volume);
+                continue;
+            }
+
+            self.inform(format_args!(
+                "Switching volume {} primary location from {} to {}",
+                volume.name,
+                volume.show_active_location(),
+                to.show()
+            ))?;
+            self.api
+                .set_volume_primary(state.clone(), volume.name.clone(), to)
+                .await?;
+            let volume = self
+                .wait_for_primary_location_helper(state, &volume.name, &to)
+                .await?;
+            failed_over.push(volume);
+        }
+
+        Ok(failed_over)
+    }
+
+    async fn wait_for_primary_location_helper(
+        &self,
+        state: &v0::StateName,
+        volume: &v0::VolumeName,
+        location: &Location,
+    ) -> anyhow::Result<v0::Volume> {
+        let delay = Duration::from_secs(5);
+        loop {
+            let volume = self.api.get_volume(state, volume).await?.into_inner();
+            if volume.primary_location().as_ref() == Some(location) {
+                return Ok(volume);
+            }
+            time::sleep(delay).await;
+        }
+    }
+}
+
+/// Refuse unsafe failover unless forced, in which case the problems are only logged
+pub(super) fn ensure_failover_is_safe(
+    state: &v0::State,
+    volumes: &[v0::Volume],
+    to: &Location,
+    force: bool,
+) -> anyhow::Result<()> {
+    let problems = failover_problems(state, volumes, to);
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    if force {
+        for problem in &problems {
+            log::warn!("{}", problem);
+        }
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "Cannot fail over to {}:\n  {}\nUse '--force' to fail over anyway",
+            to.show(),
+            problems.join("\n  ")
+        );
+    }
+}
+
+/// Everything that makes failover to the location unsafe
+fn failover_problems(state: &v0::State, volumes: &[v0::Volume], to: &Location) -> Vec<String> {
+    let mut problems = vec![];
+
+    match state.locations.status(to) {
+        Some(v0::StateLocationStatus::Ok) => {}
+        Some(status) => problems.push(format!(
+            "State {} location {} is {}",
+            state.name,
+            to.show(),
+            status.as_str()
+        )),
+        None => problems.push(format!(
+            "State {} is not available in {}",
+            state.name,
+            to.show()
+        )),
+    }
+
+    for volume in volumes {
+        match volume.location(to) {
+            Some(location) if location.is_synchronized() => {}
+            Some(location) => {
+                let progress = location
+                    .progress
+                    .as_ref()
+                    .map(|progress| format!(", {} synchronized", progress.show()))
+                    .unwrap_or_default();
+                problems.push(format!(
+                    "Volume {} in {} is {}{}",
+                    volume.name,
+                    to.show(),
+                    location.status.value.as_str(),
+                    progress
+                ));
+            }
+            None => problems.push(format!(
+                "Volume {} has no replica in {}",
+                volume.name,
+                to.show()
+            )),
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v0::fixtures;
+
+    use super::*;
+
+    fn volume(status: &str, synchronized: u64) -> v0::Volume {
+        fixtures::volume(
+            "data",
+            Some("aws/us-east-1"),
+            &[
+                ("aws/us-east-1", "ok", None),
+                ("aws/eu-west-1", status, Some(synchronized)),
+            ],
+        )
+    }
+
+    fn state() -> v0::State {
+        fixtures::state("alfa", &[("aws/us-east-1", "ok"), ("aws/eu-west-1", "ok")])
+    }
+
+    #[test]
+    fn failover_to_synchronized_location() {
+        let to = "aws/eu-west-1".parse::<Location>().unwrap();
+        let problems = failover_problems(&state(), &[volume("ok", 100)], &to);
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn failover_to_syncing_location() {
+        let to = "aws/eu-west-1".parse::<Location>().unwrap();
+        let problems = failover_problems(&state(), &[volume("ok", 42)], &to);
+        assert_eq!(
+            problems,
+            ["Volume data in aws/eu-west-1 is ok, 42% synchronized"]
+        );
+    }
+
+    #[test]
+    fn failover_to_missing_location() {
+        let to = "aws/us-west-2".parse::<Location>().unwrap();
+        let problems = failover_problems(&state(), &[volume("ok", 100)], &to);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+    }
+}
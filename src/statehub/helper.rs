@@ -107,6 +107,30 @@ impl StateHub {
         }
     }
 
+    pub(super) async fn warn_disallowed_states_helper(
+        &self,
+        cluster: &v0::ClusterName,
+        states: &[v0::StateName],
+    ) -> anyhow::Result<()> {
+        for state in states {
+            // Missing states are reported when adding locations
+            let state = match self.api.get_state(state).await {
+                Ok(state) => state,
+                Err(_) => continue,
+            };
+            if !state.is_cluster_allowed(cluster) {
+                log::warn!(
+                    "Cluster {} is not allowed to use state {}, run 'statehub state allow {} {}'",
+                    cluster,
+                    state.name,
+                    state.name,
+                    cluster
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub(super) async fn adjust_all_states(
         &self,
         states: &[v0::StateName],
@@ -219,11 +243,15 @@ or run following commands before running 'statehub register-cluster`
         &self,
         cluster: &v0::Cluster,
         helm: &k8s::Helm,
+        settings: &[k8s::AgentSetting],
     ) -> anyhow::Result<()> {
+        let context = helm.context();
         let namespace = helm.namespace();
         let default_state = helm.default_state().unwrap_or("");
         let api = self.api.url("");
-        k8s::store_configmap(namespace, &cluster.name, default_state, &api).await?;
+        let config = k8s::ClusterAgentConfig::new(&cluster.name, default_state, &api)
+            .with_settings(settings);
+        k8s::store_configmap(context, namespace, &config).await?;
         Ok(())
     }
 
@@ -234,8 +262,9 @@ or run following commands before running 'statehub register-cluster`
     ) -> anyhow::Result<()> {
         let token = self.api.issue_cluster_token(&cluster.name).await?;
         self.verbosely(format!("Issued token {} for {}", token.token, cluster))?;
+        let context = helm.context();
         let namespace = helm.namespace();
-        k8s::store_cluster_token(namespace, &token.token).await?;
+        k8s::store_cluster_token(context, namespace, &token.token).await?;
         Ok(())
     }
 
@@ -246,9 +275,20 @@ or run following commands before running 'statehub register-cluster`
     ) -> anyhow::Result<()> {
         if let Some(states) = states {
             for state in states {
-                if self.api.get_state(&state).await?.owner.is_none() {
-                    self.verbosely(format!("Claiming ownership of state {}", state))?;
-                    self.api.set_owner(&state, &cluster.name).await?;
+                match self.api.get_state(&state).await?.into_inner().owner {
+                    Some(owner) if owner == cluster.name => {
+                        self.verbosely(format_args!("Cluster already owns state {}", state))?;
+                    }
+                    Some(owner) => {
+                        self.inform(format_args!(
+                            "State {} is owned by cluster {}, not claiming ownership",
+                            state, owner
+                        ))?;
+                    }
+                    None => {
+                        self.api.set_owner(&state, &cluster.name).await?;
+                        self.inform(format_args!("Claimed ownership of state {}", state))?;
+                    }
                 }
             }
         }
@@ -278,20 +318,175 @@ or run following commands before running 'statehub register-cluster`
         volume: &v0::VolumeName,
         wait: bool,
     ) -> anyhow::Result<Output<v0::Volume>> {
-        let mut volume = self.api.delete_volume(state, volume).await?;
+        let volume = self.api.delete_volume(state, volume).await?;
         if wait {
-            let delay = Duration::from_secs(5);
-            loop {
-                match self.api.get_volume(state, &volume.name).await {
-                    Ok(deleting) => volume = deleting,
-                    Err(err) if is_volume_not_found(&err) => break,
-                    _ => continue,
+            self.wait_for_volume_deleted_helper(state, &volume.name)
+                .await?;
+        }
+
+        Ok(volume)
+    }
+
+    pub(super) async fn wait_for_volume_deleted_helper(
+        &self,
+        state: &v0::StateName,
+        volume: &v0::VolumeName,
+    ) -> anyhow::Result<()> {
+        let delay = Duration::from_secs(5);
+        loop {
+            match self.api.get_volume(state, volume).await {
+                Ok(deleting) => {
+                    self.verbosely(format_args!("Waiting for volume {}", deleting.name))?
                 }
+                Err(err) if is_volume_not_found(&err) => break Ok(()),
+                Err(err) => log::debug!("Polling volume {}: {}", volume, err),
             }
             time::sleep(delay).await;
         }
+    }
 
-        Ok(volume)
+    /// Poll the volume until it is done with whatever it is busy with
+    pub(super) async fn wait_for_volume_helper(
+        &self,
+        state: &v0::StateName,
+        volume: &v0::VolumeName,
+        done: impl Fn(&v0::Volume) -> bool,
+    ) -> anyhow::Result<v0::Volume> {
+        let delay = Duration::from_secs(5);
+        loop {
+            let volume = self.api.get_volume(state, volume).await?.into_inner();
+            if done(&volume) {
+                return Ok(volume);
+            }
+            for location in &volume.locations {
+                self.verbosely(format_args!(
+                    "Waiting for volume {} in {}: {}",
+                    volume.name,
+                    location.name,
+                    location.show()
+                ))?;
+            }
+            time::sleep(delay).await;
+        }
+    }
+
+    /// Not found errors get a hint with similarly named existing objects
+    pub(super) async fn suggest_names_helper(&self, err: anyhow::Error) -> anyhow::Error {
+        let missing = match err.downcast_ref::<v0::Error>().and_then(v0::Error::missing) {
+            Some(missing) => missing,
+            None => return err,
+        };
+
+        let names = match missing {
+            v0::Missing::State(_) => self.api.get_all_states().await.map(|states| {
+                states
+                    .into_inner()
+                    .into_iter()
+                    .map(|state| state.name.to_string())
+                    .collect::<Vec<_>>()
+            }),
+            v0::Missing::Cluster(_) => self.api.get_all_clusters().await.map(|clusters| {
+                clusters
+                    .into_inner()
+                    .into_iter()
+                    .map(|cluster| cluster.name.to_string())
+                    .collect()
+            }),
+            v0::Missing::Volume(state, _) => self.api.get_all_volumes(state).await.map(|volumes| {
+                volumes
+                    .into_inner()
+                    .into_iter()
+                    .map(|volume| volume.name.to_string())
+                    .collect()
+            }),
+        };
+        let names = match names {
+            Ok(names) => names,
+            Err(_) => return err,
+        };
+
+        add_suggestions(err, names.iter().map(String::as_str))
+    }
+
+    pub(super) async fn wait_for_state_deleted_helper(
+        &self,
+        state: &v0::StateName,
+    ) -> anyhow::Result<()> {
+        let delay = Duration::from_secs(5);
+        loop {
+            match self.api.get_state(state).await {
+                Ok(deleting) => {
+                    self.verbosely(format_args!("Waiting for state {}", deleting.show()))?
+                }
+                Err(err) if is_state_not_found(&err) => break Ok(()),
+                Err(err) => log::debug!("Polling state {}: {}", state, err),
+            }
+            time::sleep(delay).await;
+        }
+    }
+
+    /// Registered clusters that use the state as their default storage class.
+    /// Only clusters with a known and reachable kubeconfig context can be checked,
+    /// the ones that cannot are reported so that the guard is not silently partial.
+    pub(super) async fn default_state_clusters_helper(
+        &self,
+        state: &v0::StateName,
+        namespace: &str,
+    ) -> anyhow::Result<Vec<v0::ClusterName>> {
+        let mut clusters = vec![];
+        for cluster in self.api.get_all_clusters().await?.into_inner() {
+            let context = if let Some(context) = k8s::find_context(&cluster.name) {
+                context
+            } else {
+                self.inform(format_args!(
+                    "Cannot check default storage class of cluster {}, no kubeconfig context found",
+                    cluster.name
+                ))?;
+                continue;
+            };
+
+            let configmap = match k8s::get_configmap(Some(&context), namespace).await {
+                Ok(Some(configmap)) => configmap,
+                Ok(None) => continue,
+                Err(err) => {
+                    self.inform(format_args!(
+                        "Cannot check default storage class of cluster {}: {}",
+                        cluster.name, err
+                    ))?;
+                    continue;
+                }
+            };
+            match k8s::ClusterAgentConfig::from_data(&configmap.data) {
+                Ok(config) if config.get("default-state").as_deref() == Some(state.as_ref()) => {
+                    clusters.push(cluster.name)
+                }
+                Ok(_) => {}
+                Err(err) => self.inform(format_args!(
+                    "Cannot check default storage class of cluster {}, invalid statehub configmap: {}",
+                    cluster.name, err
+                ))?,
+            }
+        }
+        Ok(clusters)
+    }
+
+    /// Report individual failures of concurrently processed items
+    pub(super) fn report_results<T>(
+        &self,
+        results: impl IntoIterator<Item = (T, anyhow::Result<()>)>,
+    ) -> anyhow::Result<()>
+    where
+        T: fmt::Display,
+    {
+        let mut failed = 0;
+        for (item, result) in results {
+            if let Err(err) = result {
+                failed += 1;
+                self.error(format_args!("{}: {:#}", item, err))?;
+            }
+        }
+        anyhow::ensure!(failed == 0, "{} operation(s) failed", failed);
+        Ok(())
     }
 
     pub(super) fn login_prompt_helper(&self) -> anyhow::Result<(String, String)> {
@@ -317,6 +512,35 @@ or run following commands before running 'statehub register-cluster`
     }
 }
 
+/// Bare API errors get the hint in their message,
+/// errors wrapped with context keep their chain and get the hint on top
+fn add_suggestions<'a>(
+    err: anyhow::Error,
+    names: impl IntoIterator<Item = &'a str>,
+) -> anyhow::Error {
+    let bare = matches!(err.chain().next(), Some(outer) if outer.is::<v0::Error>());
+    if bare {
+        match err.downcast::<v0::Error>() {
+            Ok(error) => error.with_suggestions(names).into(),
+            Err(err) => err,
+        }
+    } else {
+        match err
+            .downcast_ref::<v0::Error>()
+            .and_then(|error| error.suggestion(names))
+        {
+            Some(hint) => err.context(hint),
+            None => err,
+        }
+    }
+}
+
+fn is_state_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<v0::Error>()
+        .map(v0::Error::is_state_not_found)
+        .unwrap_or_default()
+}
+
 fn is_volume_not_found(err: &anyhow::Error) -> bool {
     err.downcast_ref::<v0::Error>()
         .map(v0::Error::is_volume_not_found)
@@ -373,4 +597,35 @@ mod tests {
         assert_eq!(cmds[2], "statehub add-location --wait bravo eastus2");
         assert_eq!(cmds[3], "statehub add-location --wait charlie us-west-2");
     }
+
+    fn cluster_not_found() -> v0::Error {
+        let text = r#"{"httpCode":404,"httpStatus":"Not Found","error":{"errorCode":"ClusterNotFound","cluster":"prod-eu"},"msg":"Cluster prod-eu does not exist"}"#;
+        json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn suggestions_for_bare_error() {
+        let err = add_suggestions(cluster_not_found().into(), vec!["prod-eu1", "staging"]);
+        assert_eq!(
+            err.to_string(),
+            "Cluster prod-eu does not exist, did you mean `prod-eu1`?"
+        );
+        assert!(err.is::<v0::Error>());
+    }
+
+    #[test]
+    fn suggestions_keep_context() {
+        let err = anyhow::Error::from(cluster_not_found())
+            .context("State alfa is left without owner, restoring cluster prod-eu failed");
+        let err = add_suggestions(err, vec!["prod-eu1", "staging"]);
+        let chain = err.chain().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            chain,
+            [
+                "did you mean `prod-eu1`?",
+                "State alfa is left without owner, restoring cluster prod-eu failed",
+                "Cluster prod-eu does not exist",
+            ]
+        );
+    }
 }
@@ -0,0 +1,155 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::str;
+
+use super::*;
+
+/// `--label` and `--annotation` options of the create commands
+#[derive(Debug, StructOpt)]
+pub(super) struct MetadataOptions {
+    #[structopt(
+        help = "Label as key=value, can be repeated",
+        long = "label",
+        number_of_values = 1
+    )]
+    labels: Vec<v0::Label>,
+    #[structopt(
+        help = "Annotation as key=value, can be repeated",
+        long = "annotation",
+        number_of_values = 1
+    )]
+    annotations: Vec<v0::Annotation>,
+}
+
+/// Kind of object to label, volumes are named as `state/volume`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum ObjectKind {
+    State,
+    Cluster,
+    Volume,
+}
+
+/// Whether changes go to labels or to annotations
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MetadataField {
+    Labels,
+    Annotations,
+}
+
+impl MetadataOptions {
+    pub(super) fn metadata(self) -> v0::Metadata {
+        v0::Metadata::new(self.labels, self.annotations)
+    }
+}
+
+impl str::FromStr for ObjectKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "state" | "states" | "st" => Ok(Self::State),
+            "cluster" | "clusters" | "cl" => Ok(Self::Cluster),
+            "volume" | "volumes" | "v" => Ok(Self::Volume),
+            other => anyhow::bail!("Unknown kind {}, expected state, cluster or volume", other),
+        }
+    }
+}
+
+impl MetadataField {
+    fn select(self, metadata: &v0::Metadata) -> v0::Labels {
+        match self {
+            Self::Labels => metadata.labels.clone(),
+            Self::Annotations => metadata.annotations.clone(),
+        }
+    }
+
+    fn update(self, labels: v0::Labels) -> v0::UpdateMetadataDto {
+        match self {
+            Self::Labels => v0::UpdateMetadataDto {
+                labels: Some(labels),
+                annotations: None,
+            },
+            Self::Annotations => v0::UpdateMetadataDto {
+                labels: None,
+                annotations: Some(labels),
+            },
+        }
+    }
+}
+
+impl StateHub {
+    pub(super) async fn label(
+        &self,
+        kind: ObjectKind,
+        name: String,
+        changes: Vec<v0::LabelChange>,
+    ) -> anyhow::Result<()> {
+        self.update_metadata_helper(kind, name, MetadataField::Labels, |labels| {
+            changes.iter().for_each(|change| change.apply(labels))
+        })
+        .await
+    }
+
+    pub(super) async fn annotate(
+        &self,
+        kind: ObjectKind,
+        name: String,
+        changes: Vec<v0::AnnotationChange>,
+    ) -> anyhow::Result<()> {
+        self.update_metadata_helper(kind, name, MetadataField::Annotations, |annotations| {
+            changes.iter().for_each(|change| change.apply(annotations))
+        })
+        .await
+    }
+
+    async fn update_metadata_helper(
+        &self,
+        kind: ObjectKind,
+        name: String,
+        field: MetadataField,
+        change: impl Fn(&mut v0::Labels),
+    ) -> anyhow::Result<()> {
+        let apply = |metadata: &v0::Metadata| {
+            let mut labels = field.select(metadata);
+            change(&mut labels);
+            field.update(labels)
+        };
+
+        match kind {
+            ObjectKind::State => {
+                let state = v0::StateName::from(name);
+                let current = self.api.get_state(&state).await?;
+                self.api
+                    .update_state_metadata(&state, apply(&current.metadata))
+                    .await
+                    .map(Detailed)
+                    .print(&self.stdout, self.json)
+            }
+            ObjectKind::Cluster => {
+                let cluster = v0::ClusterName::from(name);
+                let current = self.api.get_cluster(&cluster).await?;
+                self.api
+                    .update_cluster_metadata(&cluster, apply(&current.metadata))
+                    .await
+                    .map(Detailed)
+                    .print(&self.stdout, self.json)
+            }
+            ObjectKind::Volume => {
+                let (state, volume) = name.split_once('/').ok_or_else(|| {
+                    anyhow::anyhow!("Invalid volume {}, expected state/volume", name)
+                })?;
+                let state = v0::StateName::from(state);
+                let volume = v0::VolumeName::from(volume.to_string());
+                let current = self.api.get_volume(&state, &volume).await?;
+                self.api
+                    .update_volume_metadata(&state, &volume, apply(&current.metadata))
+                    .await
+                    .map(Detailed)
+                    .print(&self.stdout, self.json)
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::time::Duration;
+
+use tokio::time;
+
+use super::*;
+
+/// Minimal number of healthy locations for the state volumes to stay redundant
+const REDUNDANT_LOCATIONS: usize = 2;
+
+/// What removing a location does to the state and its volumes
+#[derive(Debug, Default, PartialEq)]
+struct Removal {
+    /// Volumes with a replica in the location
+    affected: Vec<v0::VolumeName>,
+    /// Volumes whose primary location it is
+    primary: Vec<v0::VolumeName>,
+    /// Volumes with no synchronized replica anywhere else
+    last_replica: Vec<v0::VolumeName>,
+    /// Healthy state locations before the removal
+    healthy: usize,
+    /// Healthy state locations left after the removal
+    remaining: usize,
+}
+
+impl Removal {
+    /// Only a removal that takes the state from redundant to not redundant is worth a warning
+    fn lowers_redundancy(&self) -> bool {
+        self.healthy >= REDUNDANT_LOCATIONS && self.remaining < REDUNDANT_LOCATIONS
+    }
+}
+
+impl StateHub {
+    pub(super) async fn remove_location(
+        &self,
+        state: v0::StateName,
+        location: Location,
+        failover_to: Option<Location>,
+        wait: bool,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let current = self.api.get_state(&state).await?.into_inner();
+        if !current.is_available_in(&location) {
+            return self
+                .inform(format_args!(
+                    "State {} is not available in {:#}",
+                    state, location
+                ))
+                .map_err(Into::into);
+        }
+
+        let volumes = self.api.get_all_volumes(state.clone()).await?.into_inner();
+        let removal = removal_impact(&current, &volumes, &location);
+        if !removal.affected.is_empty() {
+            self.inform(format_args!(
+                "Volumes with replica in {:#}: {}",
+                location,
+                removal.affected.iter().join(", ")
+            ))?;
+        }
+
+        if !removal.primary.is_empty() {
+            let primary = volumes
+                .iter()
+                .filter(|volume| removal.primary.contains(&volume.name))
+                .cloned()
+                .collect::<Vec<_>>();
+            if let Some(to) = failover_to {
+                failover::ensure_failover_is_safe(&current, &primary, &to, force)?;
+                self.failover_helper(&state, primary, to).await?;
+            } else if force {
+                self.inform(format_args!(
+                    "Volumes {} are primary in {:#} and will lose their primary location",
+                    removal.primary.iter().join(", "),
+                    location
+                ))?;
+            } else {
+                anyhow::bail!(
+                    "Volumes {} are primary in {:#}\n{}",
+                    removal.primary.iter().join(", "),
+                    location,
+                    "Use '--failover-to <location>' to fail them over first, or '--force' to remove anyway"
+                );
+            }
+        }
+
+        if !removal.last_replica.is_empty() {
+            if force {
+                self.inform(format_args!(
+                    "Volumes {} have no other synchronized replica than {:#}",
+                    removal.last_replica.iter().join(", "),
+                    location
+                ))?;
+            } else {
+                anyhow::bail!(
+                    "Volumes {} have no other synchronized replica than {:#}\n{}",
+                    removal.last_replica.iter().join(", "),
+                    location,
+                    "Use '--force' to remove anyway"
+                );
+            }
+        }
+
+        if removal.lowers_redundancy() {
+            self.inform(format_args!(
+                "State {} will be left with {} healthy location(s), its volumes will not be redundant",
+                state,
+                removal.remaining
+            ))?;
+        }
+
+        self.inform(format_args!("Removing state {} from {:#}", state, location))?;
+        self.remove_location_helper(&current, &location).await?;
+
+        if wait {
+            self.wait_for_location_removed_helper(&state, &location)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Location is first marked as deleting, then disappears from the state
+    async fn wait_for_location_removed_helper(
+        &self,
+        state: &v0::StateName,
+        location: &Location,
+    ) -> anyhow::Result<()> {
+        let delay = Duration::from_secs(5);
+        loop {
+            let current = self.api.get_state(state).await?;
+            match current.locations.status(location) {
+                None => return Ok(()),
+                Some(v0::StateLocationStatus::Error) => anyhow::bail!(
+                    "State {} location {:#} failed to be removed",
+                    state,
+                    location
+                ),
+                Some(status) => self.verbosely(format_args!(
+                    "Waiting for state {} location {:#}: {}",
+                    state,
+                    location,
+                    status.as_str()
+                ))?,
+            }
+            time::sleep(delay).await;
+        }
+    }
+}
+
+fn removal_impact(state: &v0::State, volumes: &[v0::Volume], location: &Location) -> Removal {
+    let mut removal = Removal::default();
+
+    for volume in volumes
+        .iter()
+        .filter(|volume| volume.location(location).is_some())
+    {
+        removal.affected.push(volume.name.clone());
+        if volume.primary_location().as_ref() == Some(location) {
+            removal.primary.push(volume.name.clone());
+        }
+        let synchronized_elsewhere = volume.locations.iter().any(|replica| {
+            replica.location().as_ref() != Some(location) && replica.is_synchronized()
+        });
+        if !synchronized_elsewhere {
+            removal.last_replica.push(volume.name.clone());
+        }
+    }
+
+    let healthy = state
+        .all_locations()
+        .into_iter()
+        .filter(|other| state.locations.status(other) == Some(v0::StateLocationStatus::Ok))
+        .collect::<Vec<_>>();
+    removal.healthy = healthy.len();
+    removal.remaining = healthy.iter().filter(|other| *other != location).count();
+
+    removal
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v0::fixtures;
+
+    use super::*;
+
+    fn volume(active: &str, status: &str) -> v0::Volume {
+        fixtures::volume(
+            "data",
+            Some(active),
+            &[
+                ("aws/us-east-1", "ok", None),
+                ("aws/eu-west-1", status, None),
+            ],
+        )
+    }
+
+    fn state() -> v0::State {
+        fixtures::state("alfa", &[("aws/us-east-1", "ok"), ("aws/eu-west-1", "ok")])
+    }
+
+    #[test]
+    fn remove_secondary_location() {
+        let location = "aws/eu-west-1".parse::<Location>().unwrap();
+        let removal = removal_impact(&state(), &[volume("aws/us-east-1", "ok")], &location);
+        assert_eq!(removal.affected, [v0::VolumeName::from("data".to_string())]);
+        assert!(removal.primary.is_empty());
+        assert!(removal.last_replica.is_empty());
+        assert_eq!(removal.remaining, 1);
+        assert!(removal.lowers_redundancy());
+    }
+
+    #[test]
+    fn remove_from_non_redundant_state() {
+        let state = fixtures::state(
+            "alfa",
+            &[("aws/us-east-1", "ok"), ("aws/eu-west-1", "provisioning")],
+        );
+        let location = "aws/eu-west-1".parse::<Location>().unwrap();
+        let removal = removal_impact(&state, &[], &location);
+        assert_eq!(removal.healthy, 1);
+        assert_eq!(removal.remaining, 1);
+        assert!(!removal.lowers_redundancy());
+    }
+
+    #[test]
+    fn remove_primary_location() {
+        let location = "aws/us-east-1".parse::<Location>().unwrap();
+        let removal = removal_impact(&state(), &[volume("aws/us-east-1", "ok")], &location);
+        assert_eq!(removal.primary, removal.affected);
+        assert!(removal.last_replica.is_empty());
+    }
+
+    #[test]
+    fn remove_last_synchronized_replica() {
+        let location = "aws/us-east-1".parse::<Location>().unwrap();
+        let removal = removal_impact(
+            &state(),
+            &[volume("aws/us-east-1", "provisioning")],
+            &location,
+        );
+        assert_eq!(removal.last_replica, removal.affected);
+    }
+}
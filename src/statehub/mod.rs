@@ -5,11 +5,14 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::fs;
 use std::io;
+use std::path::PathBuf;
 
 use anyhow::Context;
 use console::Term;
 use dialoguer::{theme, Confirm, Input};
+use futures::future;
 use itertools::Itertools;
 use serde::{de, Deserialize, Serialize};
 use structopt::StructOpt;
@@ -25,11 +28,26 @@ use crate::Output;
 
 use config::Config;
 use helper::AddLocation;
-use print::{ClusterAndStates, Print, StateAndClusters};
+use label::{MetadataOptions, ObjectKind};
+use print::{ClusterAndStates, Print, StateAndClusters, VolumeAndClusters, VolumeAndConsumers};
+use select::{Selection, Selector};
+use topology::TopologyFormat;
 
+mod agent;
 mod config;
+mod doctor;
+mod failover;
 mod helper;
+mod label;
+mod location;
 mod print;
+mod recommend;
+mod select;
+mod status;
+mod storage;
+mod token;
+mod topology;
+mod volume;
 
 const ABOUT: &str = "statehub CLI tool";
 
@@ -74,16 +92,56 @@ enum Command {
         owner: Option<v0::ClusterName>,
         #[structopt(long, short, help = "Location definition")]
         location: Vec<Location>,
+        #[structopt(long, help = "K8s storage class name [default: state name]")]
+        storage_class: Option<String>,
+        #[structopt(
+            long,
+            help = "Storage class volume binding mode (WaitForFirstConsumer or Immediate)"
+        )]
+        binding_mode: Option<v0::VolumeBindingMode>,
+        #[structopt(long, help = "Storage class file system type [default: ext4]")]
+        fs_type: Option<v0::VolumeFileSystem>,
+        #[structopt(long, help = "Comma separated storage class mount options")]
+        mount_options: Option<String>,
+        #[structopt(
+            long = "allow-cluster",
+            help = "Allow only this cluster to use the state, can be repeated",
+            number_of_values = 1
+        )]
+        allowed_clusters: Vec<v0::ClusterName>,
+        #[structopt(flatten)]
+        metadata: MetadataOptions,
     },
 
     #[structopt(about = "Delete existing state", aliases = &["delete-st", "ds"], display_order(20))]
     DeleteState {
         #[structopt(help = "State name")]
         name: v0::StateName,
+        #[structopt(help = "Delete state volumes as well", long)]
+        cascade: bool,
+        #[structopt(help = "Wait until state is deleted", long)]
+        wait: bool,
+        #[structopt(help = "Do not ask for confirmation", long, short)]
+        force: bool,
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
     },
 
     #[structopt(about = "List available states", aliases = &["list-state", "list-st", "ls"], display_order(20))]
-    ListStates,
+    ListStates {
+        #[structopt(help = "List only states owned by this cluster", long)]
+        owner: Option<v0::ClusterName>,
+        #[structopt(
+            help = "List only states matching the selector, e.g. 'env=prod,team'",
+            long,
+            short = "l"
+        )]
+        selector: Option<Selector>,
+    },
 
     #[structopt(about = "Show state details", aliases = &["show-s", "ss"], display_order(20))]
     ShowState {
@@ -91,6 +149,12 @@ enum Command {
         name: v0::StateName,
     },
 
+    #[structopt(about = "State access control operations", display_order(20))]
+    State {
+        #[structopt(subcommand)]
+        command: StateCommand,
+    },
+
     #[structopt(about = "Register new cluster", aliases = &["register-cl", "rc"], display_order(10))]
     RegisterCluster {
         #[structopt(help = "Cluster name, defaults to current k8s context")]
@@ -140,6 +204,40 @@ enum Command {
 
         #[structopt(help = "K8s cluster provider [default: autodetect]", long)]
         provider: Option<v0::Provider>,
+
+        #[structopt(
+            help = "How long statehub components wait before cleaning up, e.g. 600s or 10m",
+            long
+        )]
+        cleanup_grace: Option<k8s::Duration>,
+
+        #[structopt(help = "Statehub components log level", long)]
+        log_level: Option<k8s::LogLevel>,
+
+        #[structopt(
+            help = "Additional statehub configmap setting, can be repeated",
+            long = "set",
+            number_of_values = 1
+        )]
+        settings: Vec<k8s::AgentSetting>,
+
+        #[structopt(
+            help = "Kubeconfig context of the cluster to register, can be repeated",
+            long = "context",
+            number_of_values = 1,
+            conflicts_with = "all_contexts"
+        )]
+        contexts: Vec<String>,
+
+        #[structopt(
+            help = "Register clusters from all kubeconfig contexts",
+            long,
+            conflicts_with = "name"
+        )]
+        all_contexts: bool,
+
+        #[structopt(flatten)]
+        metadata: MetadataOptions,
     },
 
     #[structopt(about = "Unregister existing cluster", aliases = &["unregister-cl", "uc"], display_order(11))]
@@ -151,12 +249,40 @@ enum Command {
     },
 
     #[structopt(about = "List registered clusters", aliases = &["list-cluster", "list-cl", "lc"], display_order(12))]
-    ListClusters,
+    ListClusters {
+        #[structopt(
+            help = "List only clusters matching the selector, e.g. 'env=prod,team'",
+            long,
+            short = "l"
+        )]
+        selector: Option<Selector>,
+    },
 
     #[structopt(about = "Show registered cluster details", aliases = &["show-cl", "sc"], display_order(13))]
     ShowCluster {
         #[structopt(help = "Cluster name")]
         name: Option<v0::ClusterName>,
+
+        #[structopt(
+            help = "Show cluster of this kubeconfig context, can be repeated",
+            long = "context",
+            number_of_values = 1,
+            conflicts_with_all = &["name", "all_contexts"]
+        )]
+        contexts: Vec<String>,
+
+        #[structopt(
+            help = "Show clusters of all kubeconfig contexts",
+            long,
+            conflicts_with = "name"
+        )]
+        all_contexts: bool,
+    },
+
+    #[structopt(about = "Registered cluster operations", display_order(14))]
+    Cluster {
+        #[structopt(subcommand)]
+        command: ClusterCommand,
     },
 
     #[structopt(
@@ -165,24 +291,35 @@ enum Command {
         display_order(30)
     )]
     AddLocation {
-        #[structopt(help = "State name")]
-        state: v0::StateName,
+        #[structopt(
+            help = "State name",
+            required_unless_one = &["selector", "all"],
+            conflicts_with_all = &["selector", "all"]
+        )]
+        state: Option<v0::StateName>,
         #[structopt(
             help = "Location specification",
-            conflicts_with = "cluster",
-            required_unless = "cluster"
+            conflicts_with_all = &["cluster", "selected-location"]
         )]
         location: Option<Location>,
+        #[structopt(
+            help = "Location specification, for states picked with --selector or --all",
+            long = "location",
+            value_name = "location",
+            conflicts_with = "cluster"
+        )]
+        selected_location: Option<Location>,
         #[structopt(
             help = "Add locations from this cluster",
             long,
             short,
-            conflicts_with = "location",
-            required_unless = "location"
+            conflicts_with = "location"
         )]
         cluster: Option<v0::ClusterName>,
         #[structopt(help = "Wait until new location is ready", long)]
         wait: bool,
+        #[structopt(flatten)]
+        selection: Selection,
     },
 
     #[structopt(
@@ -191,10 +328,35 @@ enum Command {
         display_order(30)
     )]
     RemoveLocation {
-        #[structopt(help = "State name")]
-        state: v0::StateName,
-        #[structopt(help = "Location specification")]
-        location: Location,
+        #[structopt(
+            help = "State name",
+            required_unless_one = &["selector", "all"],
+            conflicts_with_all = &["selector", "all"]
+        )]
+        state: Option<v0::StateName>,
+        #[structopt(help = "Location specification", conflicts_with = "selected-location")]
+        location: Option<Location>,
+        #[structopt(
+            help = "Location specification, for states picked with --selector or --all",
+            long = "location",
+            value_name = "location"
+        )]
+        selected_location: Option<Location>,
+        #[structopt(
+            help = "Fail over volumes that are primary in the location to this one first",
+            long
+        )]
+        failover_to: Option<Location>,
+        #[structopt(help = "Wait until the location is removed", long)]
+        wait: bool,
+        #[structopt(
+            help = "Remove even if volumes are primary or have their last synchronized replica there",
+            long,
+            short
+        )]
+        force: bool,
+        #[structopt(flatten)]
+        selection: Selection,
     },
 
     #[structopt(about = "Set state availability grade", display_order(40))]
@@ -202,10 +364,22 @@ enum Command {
 
     #[structopt(about = "Set cluster as the state owner", display_order(40))]
     SetOwner {
-        #[structopt(help = "State name")]
-        state: v0::StateName,
-        #[structopt(help = "Cluster name")]
-        cluster: v0::ClusterName,
+        #[structopt(
+            help = "State name",
+            required_unless_one = &["selector", "all"],
+            conflicts_with_all = &["selector", "all"]
+        )]
+        state: Option<v0::StateName>,
+        #[structopt(help = "Cluster name", conflicts_with = "selected-cluster")]
+        cluster: Option<v0::ClusterName>,
+        #[structopt(
+            help = "Cluster name, for states picked with --selector or --all",
+            long = "cluster",
+            value_name = "cluster"
+        )]
+        selected_cluster: Option<v0::ClusterName>,
+        #[structopt(flatten)]
+        selection: Selection,
     },
 
     #[structopt(about = "Clear state owner", display_order(40))]
@@ -216,26 +390,68 @@ enum Command {
         cluster: v0::ClusterName,
     },
 
+    #[structopt(
+        about = "Transfer state ownership to another cluster",
+        display_order(40)
+    )]
+    TransferOwner {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(help = "Current owner cluster", long)]
+        from: v0::ClusterName,
+        #[structopt(help = "New owner cluster", long)]
+        to: v0::ClusterName,
+        #[structopt(help = "Skip confirmation", long, short)]
+        force: bool,
+    },
+
     #[structopt(about = "Manually create new volume", aliases = &["create-v", "cv"], display_order(50))]
     CreateVolume {
         #[structopt(help = "State name")]
         state: v0::StateName,
         #[structopt(help = "Volume name")]
         volume: v0::VolumeName,
-        #[structopt(help = "valume size")]
-        size: u64,
-        #[structopt(help = "volume file system")]
-        fs_type: v0::VolumeFileSystem,
+        #[structopt(help = "Volume size, e.g. 500Mi, 20Gi or 1Ti (GiB if no unit)")]
+        size: v0::VolumeSize,
+        #[structopt(help = "Volume file system (state storage class one if not given)")]
+        fs_type: Option<v0::VolumeFileSystem>,
+        #[structopt(help = "Wait until volume is ready in all state locations", long)]
+        wait: bool,
+        #[structopt(flatten)]
+        metadata: MetadataOptions,
     },
 
-    #[structopt(about = "Manually delete existing volume", aliases = &["delete-v", "dv"], display_order(50))]
-    DeleteVolume {
+    #[structopt(about = "Grow existing volume", aliases = &["resize-v", "rv"], display_order(50))]
+    ResizeVolume {
         #[structopt(help = "State name")]
         state: v0::StateName,
         #[structopt(help = "Volume name")]
         volume: v0::VolumeName,
+        #[structopt(help = "New volume size, e.g. 500Mi, 20Gi or 1Ti (GiB if no unit)")]
+        size: v0::VolumeSize,
+        #[structopt(help = "Wait until volume is resized in all state locations", long)]
+        wait: bool,
+        #[structopt(
+            help = "Also resize persistent volume claims bound to the volume in the current cluster, once the volume is resized",
+            long
+        )]
+        patch_pvc: bool,
+    },
+
+    #[structopt(about = "Manually delete existing volume", aliases = &["delete-v", "dv"], display_order(50))]
+    DeleteVolume {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(
+            help = "Volume name",
+            required_unless_one = &["selector", "all"],
+            conflicts_with_all = &["selector", "all"]
+        )]
+        volume: Option<v0::VolumeName>,
         #[structopt(help = "Wait until volume is deleted", long)]
         wait: bool,
+        #[structopt(flatten)]
+        selection: Selection,
     },
 
     #[structopt(
@@ -246,10 +462,63 @@ enum Command {
     SetVolume {
         #[structopt(help = "State name")]
         state: v0::StateName,
-        #[structopt(help = "Volume name")]
-        volume: v0::VolumeName,
+        #[structopt(
+            help = "Volume name",
+            required_unless_one = &["selector", "all"],
+            conflicts_with_all = &["selector", "all"]
+        )]
+        volume: Option<v0::VolumeName>,
         #[structopt(help = "Primary location specification", long, short)]
         primary: Location,
+        #[structopt(flatten)]
+        selection: Selection,
+    },
+
+    #[structopt(
+        about = "Clear primary location of volume, e.g. during maintenance",
+        aliases = &["unset-v", "uv"],
+        display_order(50)
+    )]
+    UnsetVolumePrimary {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(help = "Volume name")]
+        volume: v0::VolumeName,
+    },
+
+    #[structopt(
+        about = "Switch primary location of state volumes once it is safe",
+        display_order(50)
+    )]
+    Failover {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(
+            help = "Volume to fail over (all volumes if not given)",
+            long = "volume",
+            number_of_values = 1
+        )]
+        volumes: Vec<v0::VolumeName>,
+        #[structopt(help = "New primary location", long)]
+        to: Location,
+        #[structopt(
+            help = "Fail over even if the location is not healthy or not synchronized",
+            long,
+            short
+        )]
+        force: bool,
+    },
+
+    #[structopt(
+        about = "Show volume details",
+        aliases = &["show-v", "shv"],
+        display_order(50)
+    )]
+    ShowVolume {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(help = "Volume name")]
+        volume: v0::VolumeName,
     },
 
     #[structopt(
@@ -260,6 +529,55 @@ enum Command {
     ListVolumes {
         #[structopt(help = "State name")]
         state: v0::StateName,
+        #[structopt(
+            help = "Also show what uses the volumes in the current Kubernetes cluster",
+            long
+        )]
+        k8s: bool,
+        #[structopt(
+            help = "List only volumes matching the selector, e.g. 'env=prod,team'",
+            long,
+            short = "l"
+        )]
+        selector: Option<Selector>,
+    },
+
+    #[structopt(about = "Volume operations", display_order(50))]
+    Volume {
+        #[structopt(subcommand)]
+        command: VolumeCommand,
+    },
+
+    #[structopt(
+        about = "Set or remove labels of state, cluster or volume",
+        display_order(60)
+    )]
+    Label {
+        #[structopt(help = "Object kind (state, cluster or volume)")]
+        kind: ObjectKind,
+        #[structopt(help = "Object name, volumes are given as state/volume")]
+        name: String,
+        #[structopt(
+            help = "Label changes, key=value to set or key- to remove",
+            required = true
+        )]
+        changes: Vec<v0::LabelChange>,
+    },
+
+    #[structopt(
+        about = "Set or remove annotations of state, cluster or volume",
+        display_order(60)
+    )]
+    Annotate {
+        #[structopt(help = "Object kind (state, cluster or volume)")]
+        kind: ObjectKind,
+        #[structopt(help = "Object name, volumes are given as state/volume")]
+        name: String,
+        #[structopt(
+            help = "Annotation changes, key=value to set or key- to remove",
+            required = true
+        )]
+        changes: Vec<v0::AnnotationChange>,
     },
 
     #[structopt(
@@ -304,6 +622,22 @@ enum Command {
             help = "The name of the state to configure as default storage class, nothing by default"
         )]
         default_state: Option<String>,
+
+        #[structopt(
+            help = "How long statehub components wait before cleaning up, e.g. 600s or 10m",
+            long
+        )]
+        cleanup_grace: Option<k8s::Duration>,
+
+        #[structopt(help = "Statehub components log level", long)]
+        log_level: Option<k8s::LogLevel>,
+
+        #[structopt(
+            help = "Additional statehub configmap setting, can be repeated",
+            long = "set",
+            number_of_values = 1
+        )]
+        settings: Vec<k8s::AgentSetting>,
     },
 
     #[structopt(
@@ -332,10 +666,451 @@ enum Command {
     ListRegions {
         #[structopt(help = "Also list zones", long, short)]
         zone: bool,
+
+        #[structopt(
+            help = "List regions of this kubeconfig context, can be repeated",
+            long = "context",
+            number_of_values = 1,
+            conflicts_with = "all_contexts"
+        )]
+        contexts: Vec<String>,
+
+        #[structopt(help = "List regions of all kubeconfig contexts", long)]
+        all_contexts: bool,
+    },
+
+    #[structopt(
+        about = "Recommend state locations for disaster recovery of a cluster",
+        aliases = &["recommend-l", "rcl"],
+        display_order(30)
+    )]
+    RecommendLocations {
+        #[structopt(help = "Cluster to recommend locations for", long)]
+        from_cluster: v0::ClusterName,
+        #[structopt(help = "Number of locations to recommend", long, default_value = "2")]
+        count: usize,
+    },
+
+    #[structopt(
+        about = "Show states, their locations and clusters using them",
+        display_order(30)
+    )]
+    Topology {
+        #[structopt(
+            help = "Output format (tree, matrix, dot or mermaid), cannot be combined with --json [default: tree]",
+            long
+        )]
+        format: Option<TopologyFormat>,
+    },
+
+    #[structopt(about = "List cloud regions supported by statehub", display_order(30))]
+    Regions {
+        #[structopt(help = "List only regions of this vendor (aws, azure or gcp)", long)]
+        vendor: Option<v0::Vendor>,
     },
 
     #[structopt(about = "Save default configuration file", display_order(2000))]
     SaveConfig,
+
+    #[structopt(about = "Diagnose statehub setup", display_order(2000))]
+    Doctor {
+        #[structopt(help = "Kubeconfig context to check [default: current context]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum VolumeCommand {
+    #[structopt(about = "Show persistent volume claims, pods and nodes using the volume")]
+    Consumers {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(help = "Volume name")]
+        volume: v0::VolumeName,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum StateCommand {
+    #[structopt(about = "Allow cluster to use the state")]
+    Allow {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(help = "Cluster name")]
+        cluster: v0::ClusterName,
+    },
+
+    #[structopt(about = "Disallow cluster to use the state")]
+    Disallow {
+        #[structopt(help = "State name")]
+        state: v0::StateName,
+        #[structopt(help = "Cluster name")]
+        cluster: v0::ClusterName,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum ClusterCommand {
+    #[structopt(about = "Export cluster manifests for GitOps deployment")]
+    Manifests {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(
+            help = "Namespace to install statehub components",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+
+        #[structopt(
+            help = "The name of the state to configure as default storage class",
+            long
+        )]
+        default_storage_class: Option<String>,
+
+        #[structopt(
+            help = "GitOps tool to generate manifests for (flux or argocd)",
+            long,
+            default_value = "flux"
+        )]
+        gitops: k8s::GitOps,
+
+        #[structopt(
+            help = "Issue new cluster token and write it into a secret manifest, keep it out of plain git repositories",
+            long
+        )]
+        with_token: bool,
+
+        #[structopt(
+            help = "Directory to write manifests to",
+            long,
+            short,
+            default_value = "."
+        )]
+        output: PathBuf,
+    },
+
+    #[structopt(about = "Show statehub components status in the cluster")]
+    Status {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+
+    #[structopt(about = "Cluster token operations")]
+    Token {
+        #[structopt(subcommand)]
+        command: TokenCommand,
+    },
+
+    #[structopt(about = "Statehub configmap operations")]
+    Config {
+        #[structopt(subcommand)]
+        command: ConfigCommand,
+    },
+
+    #[structopt(about = "K8s storage classes of the states")]
+    StorageClass {
+        #[structopt(subcommand)]
+        command: StorageClassCommand,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum StorageClassCommand {
+    #[structopt(about = "Create or update storage classes of the states in the cluster")]
+    Apply {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(
+            help = "State to apply storage class for, can be repeated",
+            long = "state",
+            number_of_values = 1,
+            required = true
+        )]
+        states: Vec<v0::StateName>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Replace storage class with the same name even if not managed by statehub",
+            long
+        )]
+        force: bool,
+    },
+
+    #[structopt(about = "Set or clear the cluster default storage class")]
+    SetDefault {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(
+            help = "State to make default storage class of",
+            long,
+            required_unless = "none",
+            conflicts_with = "none"
+        )]
+        state: Option<v0::StateName>,
+
+        #[structopt(help = "Clear default storage class", long)]
+        none: bool,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum ConfigCommand {
+    #[structopt(about = "Show statehub configmap settings of the cluster")]
+    Get {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(help = "Show only this setting", long)]
+        key: Option<String>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+
+    #[structopt(about = "Change statehub configmap settings of the cluster")]
+    Set {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(
+            help = "How long statehub components wait before cleaning up, e.g. 600s or 10m",
+            long
+        )]
+        cleanup_grace: Option<k8s::Duration>,
+
+        #[structopt(help = "Statehub components log level", long)]
+        log_level: Option<k8s::LogLevel>,
+
+        #[structopt(
+            help = "Additional statehub configmap setting, can be repeated",
+            long = "set",
+            number_of_values = 1
+        )]
+        settings: Vec<k8s::AgentSetting>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum TokenCommand {
+    #[structopt(about = "Issue new cluster token and replace it in the cluster")]
+    Rotate {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+
+        #[structopt(help = "Restart statehub components to pick up the new token", long)]
+        restart: bool,
+    },
+
+    #[structopt(about = "Show cluster token age and fingerprint")]
+    Show {
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+
+    #[structopt(about = "Revoke cluster token and remove it from the cluster")]
+    Revoke {
+        #[structopt(help = "Skip confirmation", long, short)]
+        force: bool,
+
+        #[structopt(help = "Cluster name, defaults to current k8s context")]
+        name: Option<v0::ClusterName>,
+
+        #[structopt(help = "Kubeconfig context of the cluster [default: autodetect]", long)]
+        context: Option<String>,
+
+        #[structopt(
+            help = "Namespace statehub components are installed in",
+            long,
+            default_value = "statehub-system"
+        )]
+        namespace: String,
+    },
+}
+
+/// Dedicated configmap options go before `--set` ones, so the latter win.
+/// Cluster identity settings only come from the registration, so they cannot be set.
+fn agent_settings(
+    cleanup_grace: Option<k8s::Duration>,
+    log_level: Option<k8s::LogLevel>,
+    settings: Vec<k8s::AgentSetting>,
+) -> anyhow::Result<Vec<k8s::AgentSetting>> {
+    if let Some(setting) = settings.iter().find(|setting| setting.is_identity()) {
+        anyhow::bail!(
+            "Setting {} cannot be changed, it comes from the cluster registration",
+            setting.key()
+        );
+    }
+    let settings = cleanup_grace
+        .map(k8s::AgentSetting::CleanupGrace)
+        .into_iter()
+        .chain(log_level.map(k8s::AgentSetting::LogLevel))
+        .chain(settings)
+        .collect();
+    Ok(settings)
+}
+
+/// Volumes are formatted by the state storage class, if the state has one,
+/// so explicit file system has to agree with it
+fn volume_file_system(
+    state: &v0::State,
+    fs: Option<v0::VolumeFileSystem>,
+) -> anyhow::Result<v0::VolumeFileSystem> {
+    let class_fs = if let Some(ref storage_class) = state.storage_class {
+        let class_fs = storage_class
+            .fs_type
+            .parse::<v0::VolumeFileSystem>()
+            .map_err(|err| {
+                anyhow::anyhow!(
+                    "State {} storage class {}: {}",
+                    state.name,
+                    storage_class.name,
+                    err
+                )
+            })?;
+        Some((storage_class, class_fs))
+    } else {
+        None
+    };
+    let fs = match (fs, class_fs) {
+        (Some(fs), _) => fs,
+        (None, Some((_, class_fs))) => class_fs,
+        (None, None) => state.storage_class().fs_type.parse()?,
+    };
+
+    anyhow::ensure!(
+        fs.is_supported(),
+        "File system {} is not supported for statehub volumes",
+        fs
+    );
+    if let Some((storage_class, class_fs)) = class_fs {
+        anyhow::ensure!(
+            fs == class_fs,
+            "State {} storage class {} formats volumes as {}, not {}",
+            state.name,
+            storage_class.name,
+            class_fs,
+            fs
+        );
+    }
+
+    Ok(fs)
+}
+
+/// Cluster name given explicitly or taken from the kubeconfig context
+fn cluster_name(
+    name: Option<v0::ClusterName>,
+    context: Option<&str>,
+) -> anyhow::Result<v0::ClusterName> {
+    name.or_else(|| k8s::get_cluster_name(context))
+        .ok_or_else(|| {
+            anyhow::anyhow!("No default Kubernetes context found, need to provide cluster name")
+        })
+}
+
+/// Pair cluster names with the kubeconfig contexts they are taken from.
+/// No contexts means current default context.
+fn cluster_contexts(
+    name: Option<v0::ClusterName>,
+    contexts: Vec<String>,
+) -> anyhow::Result<Vec<(v0::ClusterName, Option<String>)>> {
+    if contexts.is_empty() {
+        let name = name.or_else(k8s::get_current_cluster_name).ok_or_else(|| {
+            anyhow::anyhow!("No default Kubernetes context found, need to provide cluster name")
+        })?;
+        Ok(vec![(name, None)])
+    } else {
+        anyhow::ensure!(
+            name.is_none() || contexts.len() == 1,
+            "Cluster name can only be given together with a single context"
+        );
+        contexts
+            .into_iter()
+            .map(|context| {
+                let name = name
+                    .clone()
+                    .or_else(|| k8s::get_cluster_name(Some(&context)))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid context {}", context))?;
+                Ok((name, Some(context)))
+            })
+            .collect()
+    }
+}
+
+fn collect_regions(regions: HashMap<Option<String>, Vec<String>>) -> HashMap<String, Vec<String>> {
+    regions
+        .into_iter()
+        .map(|(key, value)| (key.unwrap_or_default(), value))
+        .collect()
 }
 
 impl Cli {
@@ -358,20 +1133,78 @@ impl Cli {
 
         let statehub = StateHub::new(config, self.json, self.verbose);
 
-        statehub.validate_auth().await?;
+        if !matches!(self.command, Command::Doctor { .. }) {
+            statehub.validate_auth().await?;
+        }
 
-        match self.command {
+        let result = match self.command {
             Command::Login => statehub.login().await,
             Command::CreateState {
                 name,
                 owner,
                 location,
+                storage_class,
+                binding_mode,
+                fs_type,
+                mount_options,
+                allowed_clusters,
+                metadata,
             } => {
                 let locations = location.into();
-                statehub.create_state(name, owner, locations).await
+                let storage_class = if storage_class.is_some()
+                    || binding_mode.is_some()
+                    || fs_type.is_some()
+                    || mount_options.is_some()
+                {
+                    let mut storage_class =
+                        v0::StorageClass::new(storage_class.unwrap_or_else(|| name.to_string()));
+                    if let Some(binding_mode) = binding_mode {
+                        storage_class.volume_binding_mode = binding_mode;
+                    }
+                    if let Some(fs_type) = fs_type {
+                        storage_class.fs_type = fs_type.to_string();
+                    }
+                    storage_class.mount_options = mount_options;
+                    Some(storage_class)
+                } else {
+                    None
+                };
+                let allowed_clusters = if allowed_clusters.is_empty() {
+                    None
+                } else {
+                    Some(allowed_clusters)
+                };
+                statehub
+                    .create_state(
+                        name,
+                        owner,
+                        locations,
+                        storage_class,
+                        allowed_clusters,
+                        metadata.metadata(),
+                    )
+                    .await
+            }
+            Command::State { command } => match command {
+                StateCommand::Allow { state, cluster } => {
+                    statehub.allow_cluster(state, cluster).await
+                }
+                StateCommand::Disallow { state, cluster } => {
+                    statehub.disallow_cluster(state, cluster).await
+                }
+            },
+            Command::DeleteState {
+                name: state,
+                cascade,
+                wait,
+                force,
+                namespace,
+            } => {
+                statehub
+                    .delete_state(state, cascade, wait, force, namespace)
+                    .await
             }
-            Command::DeleteState { name: state } => statehub.delete_state(state).await,
-            Command::ListStates => statehub.list_states().await,
+            Command::ListStates { owner, selector } => statehub.list_states(owner, selector).await,
             Command::ShowState { name } => statehub.show_state(&name).await,
             Command::RegisterCluster {
                 name,
@@ -383,12 +1216,16 @@ impl Cli {
                 namespace,
                 skip_helm,
                 provider,
+                cleanup_grace,
+                log_level,
+                settings,
+                contexts,
+                all_contexts,
+                metadata,
             } => {
-                let name = name.or_else(k8s::get_current_cluster_name).ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "No default Kubernetes context found, need to provide cluster name"
-                    )
-                })?;
+                let settings = agent_settings(cleanup_grace, log_level, settings)?;
+                let contexts = k8s::resolve_contexts(contexts, all_contexts)?;
+                let clusters = cluster_contexts(name, contexts)?;
                 let no_default_storage_class = if no_state {
                     true
                 } else {
@@ -405,58 +1242,284 @@ impl Cli {
                 let claim_unowned_states = !no_state_owner;
                 let helm = k8s::Helm::new(namespace, default_state, skip_helm);
                 statehub
-                    .register_cluster(name, provider, states, helm, claim_unowned_states)
+                    .register_clusters(
+                        clusters,
+                        provider,
+                        states,
+                        helm,
+                        claim_unowned_states,
+                        &settings,
+                        &metadata.metadata(),
+                    )
                     .await
             }
             Command::UnregisterCluster { force, name } => {
                 statehub.unregister_cluster(name, force).await
             }
-            Command::ListClusters => statehub.list_clusters().await,
-            Command::ShowCluster { name } => {
-                let name = name.or_else(k8s::get_current_cluster_name).ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "No default Kubernetes context found, need to provide cluster name"
-                    )
-                })?;
-                statehub.show_cluster(name).await
+            Command::ListClusters { selector } => statehub.list_clusters(selector).await,
+            Command::ShowCluster {
+                name,
+                contexts,
+                all_contexts,
+            } => {
+                let contexts = k8s::resolve_contexts(contexts, all_contexts)?;
+                let clusters = cluster_contexts(name, contexts)?;
+                statehub.show_clusters(clusters).await
             }
+            Command::Cluster { command } => match command {
+                ClusterCommand::Manifests {
+                    name,
+                    namespace,
+                    default_storage_class,
+                    gitops,
+                    with_token,
+                    output,
+                } => {
+                    let name = name.or_else(k8s::get_current_cluster_name).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "No default Kubernetes context found, need to provide cluster name"
+                        )
+                    })?;
+                    let helm = k8s::Helm::new(namespace, default_storage_class, true);
+                    statehub
+                        .cluster_manifests(name, helm, gitops, with_token, output)
+                        .await
+                }
+                ClusterCommand::Status {
+                    name,
+                    context,
+                    namespace,
+                } => {
+                    let name = cluster_name(name, context.as_deref())?;
+                    statehub.cluster_status(name, context, namespace).await
+                }
+                ClusterCommand::Token { command } => match command {
+                    TokenCommand::Rotate {
+                        name,
+                        context,
+                        namespace,
+                        restart,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        statehub
+                            .rotate_cluster_token(name, context, namespace, restart)
+                            .await
+                    }
+                    TokenCommand::Show {
+                        name,
+                        context,
+                        namespace,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        statehub.show_cluster_token(name, context, namespace).await
+                    }
+                    TokenCommand::Revoke {
+                        force,
+                        name,
+                        context,
+                        namespace,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        statehub
+                            .revoke_cluster_token(name, context, namespace, force)
+                            .await
+                    }
+                },
+                ClusterCommand::Config { command } => match command {
+                    ConfigCommand::Get {
+                        name,
+                        key,
+                        context,
+                        namespace,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        statehub
+                            .get_cluster_config(name, key, context, namespace)
+                            .await
+                    }
+                    ConfigCommand::Set {
+                        name,
+                        cleanup_grace,
+                        log_level,
+                        settings,
+                        context,
+                        namespace,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        let settings = agent_settings(cleanup_grace, log_level, settings)?;
+                        anyhow::ensure!(!settings.is_empty(), "Nothing to set");
+                        statehub
+                            .set_cluster_config(name, settings, context, namespace)
+                            .await
+                    }
+                },
+                ClusterCommand::StorageClass { command } => match command {
+                    StorageClassCommand::Apply {
+                        name,
+                        states,
+                        context,
+                        force,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        statehub
+                            .apply_storage_classes(name, states, context, force)
+                            .await
+                    }
+                    StorageClassCommand::SetDefault {
+                        name,
+                        state,
+                        none,
+                        context,
+                        namespace,
+                    } => {
+                        let name = cluster_name(name, context.as_deref())?;
+                        let state = if none { None } else { state };
+                        statehub
+                            .set_default_storage_class(name, state, context, namespace)
+                            .await
+                    }
+                },
+            },
             Command::AddLocation {
                 state,
                 location,
+                selected_location,
                 cluster,
                 wait,
+                selection,
             } => {
-                let location = location.map(AddLocation::FromLocation);
+                let location = location
+                    .or(selected_location)
+                    .map(AddLocation::FromLocation);
                 let cluster = cluster.map(AddLocation::FromCluster);
                 if let Some(add_location) = location.or(cluster) {
-                    statehub.add_location(state, add_location, wait).await
+                    statehub
+                        .for_states(state, &selection, |state| {
+                            statehub.add_location(state, add_location.clone(), wait)
+                        })
+                        .await
                 } else {
                     anyhow::bail!("Need to specify either location or --cluster");
                 }
             }
-            Command::RemoveLocation { state, location } => {
-                statehub.remove_location(state, location).await
+            Command::RemoveLocation {
+                state,
+                location,
+                selected_location,
+                failover_to,
+                wait,
+                force,
+                selection,
+            } => {
+                let location = location
+                    .or(selected_location)
+                    .ok_or_else(|| anyhow::anyhow!("Need to specify location"))?;
+                statehub
+                    .for_states(state, &selection, |state| {
+                        statehub.remove_location(state, location, failover_to, wait, force)
+                    })
+                    .await
             }
             Command::SetAvailability => statehub.set_availability().await,
-            Command::SetOwner { state, cluster } => statehub.set_owner(state, cluster).await,
+            Command::SetOwner {
+                state,
+                cluster,
+                selected_cluster,
+                selection,
+            } => {
+                let cluster = cluster
+                    .or(selected_cluster)
+                    .ok_or_else(|| anyhow::anyhow!("Need to specify cluster"))?;
+                statehub
+                    .for_states(state, &selection, |state| {
+                        statehub.set_owner(state, cluster.clone())
+                    })
+                    .await
+            }
             Command::UnsetOwner { state, cluster } => statehub.unset_owner(state, cluster).await,
+            Command::TransferOwner {
+                state,
+                from,
+                to,
+                force,
+            } => statehub.transfer_owner(state, from, to, force).await,
             Command::CreateVolume {
                 state,
                 volume,
                 size,
                 fs_type,
-            } => statehub.create_volume(state, volume, size, fs_type).await,
+                wait,
+                metadata,
+            } => {
+                statehub
+                    .create_volume(state, volume, size, fs_type, wait, metadata.metadata())
+                    .await
+            }
+            Command::ResizeVolume {
+                state,
+                volume,
+                size,
+                wait,
+                patch_pvc,
+            } => {
+                statehub
+                    .resize_volume(state, volume, size, wait, patch_pvc)
+                    .await
+            }
             Command::DeleteVolume {
                 state,
                 volume,
                 wait,
-            } => statehub.delete_volume(state, volume, wait).await,
+                selection,
+            } => {
+                statehub
+                    .for_volumes(&state, volume, &selection, |volume| {
+                        statehub.delete_volume(state.clone(), volume, wait)
+                    })
+                    .await
+            }
             Command::SetVolume {
                 state,
                 volume,
                 primary,
-            } => statehub.set_volume_primary(state, volume, primary).await,
-            Command::ListVolumes { state } => statehub.list_volumes(state).await,
+                selection,
+            } => {
+                statehub
+                    .for_volumes(&state, volume, &selection, |volume| {
+                        statehub.set_volume_primary(state.clone(), volume, primary)
+                    })
+                    .await
+            }
+            Command::UnsetVolumePrimary { state, volume } => {
+                statehub.unset_volume_primary(state, volume).await
+            }
+            Command::Failover {
+                state,
+                volumes,
+                to,
+                force,
+            } => statehub.failover(state, volumes, to, force).await,
+            Command::ShowVolume { state, volume } => statehub.show_volume(state, volume).await,
+            Command::ListVolumes {
+                state,
+                k8s,
+                selector,
+            } => statehub.list_volumes(state, k8s, selector).await,
+            Command::Label {
+                kind,
+                name,
+                changes,
+            } => statehub.label(kind, name, changes).await,
+            Command::Annotate {
+                kind,
+                name,
+                changes,
+            } => statehub.annotate(kind, name, changes).await,
+            Command::Volume { command } => match command {
+                VolumeCommand::Consumers { state, volume } => {
+                    statehub.volume_consumers(state, volume).await
+                }
+            },
             Command::CreateNamespace { namespace } => statehub.create_namespace(namespace).await,
             Command::SaveClusterToken { namespace, token } => {
                 statehub.save_cluster_token(namespace, token).await
@@ -465,16 +1528,39 @@ impl Cli {
                 namespace,
                 cluster,
                 default_state,
+                cleanup_grace,
+                log_level,
+                settings,
             } => {
+                let settings = agent_settings(cleanup_grace, log_level, settings)?;
                 statehub
-                    .setup_configmap(namespace, cluster, default_state)
+                    .setup_configmap(namespace, cluster, default_state, settings)
                     .await
             }
             Command::ListNamespaces => statehub.list_namespaces().await,
             Command::ListNodes => statehub.list_nodes().await,
             Command::ListPods => statehub.list_pods().await,
-            Command::ListRegions { zone } => statehub.list_regions(zone).await,
+            Command::ListRegions {
+                zone,
+                contexts,
+                all_contexts,
+            } => {
+                let contexts = k8s::resolve_contexts(contexts, all_contexts)?;
+                statehub.list_regions(contexts, zone).await
+            }
+            Command::RecommendLocations {
+                from_cluster,
+                count,
+            } => statehub.recommend_locations(from_cluster, count).await,
+            Command::Topology { format } => statehub.topology(format).await,
+            Command::Regions { vendor } => statehub.regions(vendor).await,
             Command::SaveConfig => statehub.save_config().await,
+            Command::Doctor { context, namespace } => statehub.doctor(context, namespace).await,
+        };
+
+        match result {
+            Err(err) => Err(statehub.suggest_names_helper(err).await),
+            ok => ok,
         }
     }
 }
@@ -542,13 +1628,17 @@ impl StateHub {
         name: v0::StateName,
         owner: Option<v0::ClusterName>,
         locations: v0::CreateStateLocationsDto,
+        storage_class: Option<v0::StorageClass>,
+        allowed_clusters: Option<Vec<v0::ClusterName>>,
+        metadata: v0::Metadata,
     ) -> anyhow::Result<()> {
         let state = v0::CreateStateDto {
             name,
-            storage_class: None,
+            storage_class,
             owner,
             locations,
-            allowed_clusters: None,
+            allowed_clusters,
+            metadata,
         };
         self.api
             .create_state(state)
@@ -556,14 +1646,111 @@ impl StateHub {
             .print(&self.stdout, self.json)
     }
 
-    async fn delete_state(&self, name: v0::StateName) -> anyhow::Result<()> {
+    async fn allow_cluster(
+        &self,
+        state: v0::StateName,
+        cluster: v0::ClusterName,
+    ) -> anyhow::Result<()> {
+        let current = self.api.get_state(&state).await?;
+        if current.allowed_clusters.is_none() {
+            self.inform(format_args!(
+                "State {} was open to any cluster, now only allowed clusters can use it",
+                state
+            ))?;
+        }
+        self.api
+            .allow_cluster(&state, &cluster)
+            .await
+            .map(Detailed)
+            .print(&self.stdout, self.json)
+    }
+
+    async fn disallow_cluster(
+        &self,
+        state: v0::StateName,
+        cluster: v0::ClusterName,
+    ) -> anyhow::Result<()> {
+        let current = self.api.get_state(&state).await?;
+        anyhow::ensure!(
+            current.allowed_clusters.is_some(),
+            "State {} is open to any cluster, use 'statehub state allow' to restrict it first",
+            state
+        );
+        if current.owner.as_ref() == Some(&cluster) {
+            log::warn!("Cluster {} is the owner of state {}", cluster, state);
+        }
         self.api
-            .delete_state(name)
+            .disallow_cluster(&state, &cluster)
             .await
+            .map(Detailed)
             .print(&self.stdout, self.json)
     }
 
-    async fn show_state(self, state: &v0::StateName) -> anyhow::Result<()> {
+    async fn delete_state(
+        &self,
+        name: v0::StateName,
+        cascade: bool,
+        wait: bool,
+        force: bool,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let volumes = self.api.get_all_volumes(name.clone()).await?.into_inner();
+        if !cascade && !volumes.is_empty() {
+            anyhow::bail!(
+                "State {} still has volumes {}, use '--cascade' to delete them as well",
+                name,
+                volumes.iter().map(|volume| &volume.name).join(", ")
+            );
+        }
+
+        let clusters = self
+            .default_state_clusters_helper(&name, &namespace)
+            .await?;
+        anyhow::ensure!(
+            clusters.is_empty(),
+            "State {} is the default storage class of cluster(s) {}, change it with 'statehub cluster storage-class set-default' first",
+            name,
+            clusters.iter().join(", ")
+        );
+
+        let prompt = if volumes.is_empty() {
+            format!("State {} will be deleted. Are you sure?", name)
+        } else {
+            format!(
+                "State {} and its {} volume(s) will be deleted. Are you sure?",
+                name,
+                volumes.len()
+            )
+        };
+        if !(force || self.confirm(prompt)) {
+            return Ok(());
+        }
+
+        // Volumes already being deleted are waited for as well, the state cannot go before them
+        let state = &name;
+        let deletions = volumes.iter().map(|volume| async move {
+            if volume.is_deleting() {
+                self.wait_for_volume_deleted_helper(state, &volume.name)
+                    .await
+            } else {
+                self.delete_volume_helper(state, &volume.name, true)
+                    .await
+                    .map(drop)
+            }
+        });
+        future::try_join_all(deletions).await?;
+        for volume in &volumes {
+            self.verbosely(format_args!("Deleted volume {}", volume.name))?;
+        }
+
+        let deleted = self.api.delete_state(name.clone()).await?;
+        if wait {
+            self.wait_for_state_deleted_helper(&name).await?;
+        }
+        deleted.print(&self.stdout, self.json)
+    }
+
+    async fn show_state(&self, state: &v0::StateName) -> anyhow::Result<()> {
         let state = self.api.get_state(state).await.map(Detailed)?;
         if let Ok(clusters) = self.api.get_all_clusters().await {
             StateAndClusters::new(state, clusters).print(&self.stdout, self.json)
@@ -572,20 +1759,113 @@ impl StateHub {
         }
     }
 
-    async fn list_states(&self) -> anyhow::Result<()> {
+    async fn list_states(
+        &self,
+        owner: Option<v0::ClusterName>,
+        selector: Option<Selector>,
+    ) -> anyhow::Result<()> {
         self.api
             .get_all_states()
             .await
+            .map(|states| {
+                states
+                    .into_inner()
+                    .into_iter()
+                    .filter(|state| owner.is_none() || state.owner == owner)
+                    .filter(|state| {
+                        selector.iter().all(|selector| {
+                            selector.matches(state.name.as_ref(), &state.metadata.labels)
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map(Output::from)
             .print(&self.stdout, self.json)
     }
 
-    async fn list_clusters(&self) -> anyhow::Result<()> {
+    async fn list_clusters(&self, selector: Option<Selector>) -> anyhow::Result<()> {
         self.api
             .get_all_clusters()
             .await
+            .map(|clusters| {
+                clusters
+                    .into_inner()
+                    .into_iter()
+                    .filter(|cluster| {
+                        selector.iter().all(|selector| {
+                            selector.matches(cluster.name.as_ref(), &cluster.metadata.labels)
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map(Output::from)
             .print(&self.stdout, self.json)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    async fn register_clusters(
+        &self,
+        clusters: Vec<(v0::ClusterName, Option<String>)>,
+        provider: Option<v0::Provider>,
+        states: Option<Vec<v0::StateName>>,
+        helm: k8s::Helm,
+        claim_unowned_states: bool,
+        settings: &[k8s::AgentSetting],
+        metadata: &v0::Metadata,
+    ) -> anyhow::Result<()> {
+        if let [(name, context)] = clusters.as_slice() {
+            let helm = helm.with_context(context.clone());
+            return self
+                .register_cluster(
+                    name.clone(),
+                    provider,
+                    states,
+                    helm,
+                    claim_unowned_states,
+                    settings,
+                    metadata,
+                )
+                .await;
+        }
+
+        // Extend the states in advance with locations of all the clusters,
+        // so that concurrent registrations do not race each other
+        if let Some(ref states) = states {
+            let locations = clusters
+                .iter()
+                .map(|(_, context)| k8s::collect_node_locations(context.as_deref()));
+            let locations = future::try_join_all(locations)
+                .await?
+                .into_iter()
+                .flatten()
+                .unique()
+                .collect::<Vec<_>>();
+            self.adjust_all_states(states, &locations, false).await?;
+        }
+
+        let results = clusters.into_iter().map(|(name, context)| {
+            let helm = helm.clone().with_context(context);
+            let states = states.clone();
+            async move {
+                let result = self
+                    .register_cluster(
+                        name.clone(),
+                        provider,
+                        states,
+                        helm,
+                        claim_unowned_states,
+                        settings,
+                        metadata,
+                    )
+                    .await;
+                (name, result)
+            }
+        });
+        let results = future::join_all(results).await;
+        self.report_results(results)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn register_cluster(
         &self,
         cluster: v0::ClusterName,
@@ -593,6 +1873,8 @@ impl StateHub {
         states: Option<Vec<v0::StateName>>,
         helm: k8s::Helm,
         claim_unowned_states: bool,
+        settings: &[k8s::AgentSetting],
+        metadata: &v0::Metadata,
     ) -> anyhow::Result<()> {
         let helm = if k8s::helm_is_found() {
             helm
@@ -601,9 +1883,11 @@ impl StateHub {
             helm.skip()
         };
 
-        let locations = k8s::collect_node_locations().await?;
+        let context = helm.context();
+        let locations = k8s::collect_node_locations(context).await?;
 
         if let Some(ref states) = states {
+            self.warn_disallowed_states_helper(&cluster, states).await?;
             self.adjust_all_states(states, &locations, false).await?;
         } else {
             self.verbosely("Skip adding this cluster to any state")?;
@@ -612,12 +1896,19 @@ impl StateHub {
         let provider = if let Some(provider) = provider {
             provider
         } else {
-            k8s::get_cluster_provider(&cluster).await?
+            let detection = k8s::get_cluster_provider(context).await?;
+            // Falling back to generic is worth explaining, e.g. for unsupported GKE
+            if detection.provider == v0::Provider::Generic {
+                self.inform(format_args!("Detected {}", detection))?;
+            } else {
+                self.verbosely(format_args!("Detected {}", detection))?;
+            }
+            detection.provider
         };
 
         let cluster = self
             .api
-            .register_cluster(&cluster, provider, &locations)
+            .register_cluster(&cluster, provider, &locations, metadata)
             .await
             .map(Quiet)?;
 
@@ -628,10 +1919,11 @@ impl StateHub {
             locations.show(),
         ))?;
 
-        k8s::validate_namespace(helm.namespace()).await?;
+        k8s::validate_namespace(context, helm.namespace()).await?;
 
         self.setup_cluster_token_helper(&cluster, &helm).await?;
-        self.setup_configmap_helper(&cluster, &helm).await?;
+        self.setup_configmap_helper(&cluster, &helm, settings)
+            .await?;
 
         if claim_unowned_states {
             self.claim_unowned_states_helper(&cluster, states).await?;
@@ -642,10 +1934,32 @@ impl StateHub {
         cluster.print(&self.stdout, self.json)
     }
 
-    async fn show_cluster(&self, name: v0::ClusterName) -> anyhow::Result<()> {
+    async fn show_clusters(
+        &self,
+        clusters: Vec<(v0::ClusterName, Option<String>)>,
+    ) -> anyhow::Result<()> {
+        if let [(name, context)] = clusters.as_slice() {
+            self.show_cluster(name.clone(), context.clone()).await
+        } else {
+            let results = clusters.into_iter().map(|(name, context)| async move {
+                let result = self.show_cluster(name.clone(), context).await;
+                (name, result)
+            });
+            let results = future::join_all(results).await;
+            self.report_results(results)
+        }
+    }
+
+    async fn show_cluster(
+        &self,
+        name: v0::ClusterName,
+        context: Option<String>,
+    ) -> anyhow::Result<()> {
         let cluster = self.api.get_cluster(&name).await.map(Detailed)?;
         if let Ok(states) = self.api.get_all_states().await {
-            ClusterAndStates::new(cluster, states).print(&self.stdout, self.json)
+            ClusterAndStates::new(cluster, states)
+                .with_context(context)
+                .print(&self.stdout, self.json)
         } else {
             cluster.print(&self.stdout, self.json)
         }
@@ -667,6 +1981,42 @@ impl StateHub {
         }
     }
 
+    async fn cluster_manifests(
+        &self,
+        name: v0::ClusterName,
+        helm: k8s::Helm,
+        gitops: k8s::GitOps,
+        token: bool,
+        output: PathBuf,
+    ) -> anyhow::Result<()> {
+        let cluster = self.api.get_cluster(&name).await?;
+        let token = if token {
+            let token = self.api.issue_cluster_token(&cluster.name).await?;
+            Some(token.into_inner().token)
+        } else {
+            None
+        };
+        let api = self.api.url("");
+        let manifests = k8s::cluster_manifests(&helm, &cluster, &api, token.as_deref(), gitops)?;
+
+        fs::create_dir_all(&output).context("Creating manifests directory")?;
+        for manifest in manifests {
+            let path = output.join(&manifest.file);
+            fs::write(&path, manifest.text)
+                .with_context(|| format!("Writing {}", path.display()))?;
+            self.inform(format_args!("Saving {}", path.display()))?;
+        }
+        if token.is_none() {
+            self.inform(format_args!(
+                "Cluster token secret is not included, create it with 'statehub cluster token rotate {} --namespace {}' once the namespace exists",
+                cluster.name,
+                helm.namespace()
+            ))?;
+        }
+
+        Ok(())
+    }
+
     async fn add_location(
         &self,
         state: v0::StateName,
@@ -703,38 +2053,46 @@ impl StateHub {
         Ok(())
     }
 
-    async fn remove_location(self, state: v0::StateName, location: Location) -> anyhow::Result<()> {
-        let state = self.api.get_state(&state).await?;
-        if state.is_available_in(&location) {
-            self.remove_location_helper(&state, &location).await?;
-        } else {
-            log::info!("{} is not availabe in {}", state, location);
-        }
-        Ok(())
-    }
-
     async fn create_volume(
-        self,
+        &self,
         state_name: v0::StateName,
         volume_name: v0::VolumeName,
-        size: u64,
-        fs: v0::VolumeFileSystem,
+        size: v0::VolumeSize,
+        fs: Option<v0::VolumeFileSystem>,
+        wait: bool,
+        metadata: v0::Metadata,
     ) -> anyhow::Result<()> {
+        let state = self.api.get_state(&state_name).await?;
+        let fs = volume_file_system(&state, fs)?;
+        if !size.is_whole_gi() {
+            self.verbosely(format_args!(
+                "Volume size {} is rounded up to {}Gi",
+                size,
+                size.size_gi()
+            ))?;
+        }
+
         let volume = v0::CreateVolumeDto {
             name: volume_name.to_string(),
-            size_gi: size,
+            size_gi: size.size_gi(),
             fs_type: fs.to_string(),
+            metadata,
         };
 
-        self.api
-            .create_volume(state_name, volume)
-            .await
-            .map(Quiet)
-            .print(&self.stdout, self.json)
+        let volume = self.api.create_volume(state_name.clone(), volume).await?;
+        if wait {
+            self.wait_for_volume_helper(&state_name, &volume.name, v0::Volume::is_ready)
+                .await
+                .map(Output::from)
+        } else {
+            Ok(volume)
+        }
+        .map(Quiet)
+        .print(&self.stdout, self.json)
     }
 
     async fn delete_volume(
-        self,
+        &self,
         state: v0::StateName,
         volume: v0::VolumeName,
         wait: bool,
@@ -769,14 +2127,48 @@ impl StateHub {
             .print(&self.stdout, self.json)
     }
 
-    async fn list_volumes(&self, state: v0::StateName) -> anyhow::Result<()> {
+    async fn unset_volume_primary(
+        &self,
+        state: v0::StateName,
+        volume: v0::VolumeName,
+    ) -> anyhow::Result<()> {
         self.api
-            .get_all_volumes(state)
+            .unset_volume_primary(state, volume)
             .await
             .print(&self.stdout, self.json)
     }
 
-    async fn set_availability(self) -> anyhow::Result<()> {
+    async fn list_volumes(
+        &self,
+        state: v0::StateName,
+        k8s: bool,
+        selector: Option<Selector>,
+    ) -> anyhow::Result<()> {
+        let volumes = self
+            .api
+            .get_all_volumes(state.clone())
+            .await?
+            .into_inner()
+            .into_iter()
+            .filter(|volume| {
+                selector
+                    .iter()
+                    .all(|selector| selector.matches(volume.name.as_ref(), &volume.metadata.labels))
+            })
+            .collect::<Vec<_>>();
+        if k8s {
+            let consumers = k8s::get_volume_consumers(None, &state, &volumes).await?;
+            let volumes = volumes
+                .into_iter()
+                .map(|volume| VolumeAndConsumers::new(volume, &consumers))
+                .collect::<Vec<_>>();
+            Output::from(volumes).print(&self.stdout, self.json)
+        } else {
+            Output::from(volumes).print(&self.stdout, self.json)
+        }
+    }
+
+    async fn set_availability(&self) -> anyhow::Result<()> {
         // Ok(Output::<String>::todo()).handle_output(&self.stdout, self.json)
         anyhow::bail!(self.show(Output::<String>::todo()))
     }
@@ -786,10 +2178,24 @@ impl StateHub {
         state: v0::StateName,
         cluster: v0::ClusterName,
     ) -> anyhow::Result<()> {
-        self.api
-            .set_owner(&state, &cluster)
-            .await
-            .print(&self.stdout, self.json)
+        match self.api.get_state(&state).await?.into_inner().owner {
+            Some(owner) if owner == cluster => {
+                self.inform(format_args!(
+                    "Cluster {} is already the owner of state {}",
+                    cluster, state
+                ))?;
+                Ok(())
+            }
+            Some(owner) => Err(v0::Error::cluster_is_state_owner(&owner, &state)).context(format!(
+                "Cannot set owner of state {}, use 'statehub transfer-owner {} --from {} --to {}'",
+                state, state, owner, cluster
+            )),
+            None => self
+                .api
+                .set_owner(&state, &cluster)
+                .await
+                .print(&self.stdout, self.json),
+        }
     }
 
     async fn unset_owner(
@@ -797,15 +2203,92 @@ impl StateHub {
         state: v0::StateName,
         cluster: v0::ClusterName,
     ) -> anyhow::Result<()> {
-        let state = self.api.get_state(&state).await?;
+        let state = self.api.get_state(&state).await?.into_inner();
 
-        if state.owner == Some(cluster) {
-            self.api
+        match state.owner {
+            Some(owner) if owner == cluster => self
+                .api
                 .unset_owner(&state.name)
                 .await
-                .print(&self.stdout, self.json)
-        } else {
-            anyhow::bail!("Permission denied, you are not theowner of this state.")
+                .print(&self.stdout, self.json),
+            Some(owner) => {
+                Err(v0::Error::cluster_is_state_owner(&owner, &state.name)).context(format!(
+                    "Permission denied, cluster {} is not the owner of state {}",
+                    cluster, state.name
+                ))
+            }
+            None => {
+                self.inform(format_args!("State {} has no owner", state.name))?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn transfer_owner(
+        &self,
+        state: v0::StateName,
+        from: v0::ClusterName,
+        to: v0::ClusterName,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let current = self.api.get_state(&state).await?.into_inner();
+        match current.owner {
+            Some(owner) if owner == from => {}
+            Some(owner) => {
+                return Err(v0::Error::cluster_is_state_owner(&owner, &state)).context(format!(
+                    "Cannot transfer ownership of state {} from cluster {}",
+                    state, from
+                ))
+            }
+            None => anyhow::bail!(
+                "State {} has no owner, use 'statehub set-owner {} {}'",
+                state,
+                state,
+                to
+            ),
+        }
+        self.api.get_cluster(&to).await?;
+
+        let prompt = format!(
+            "Transfer ownership of state {} from cluster {} to cluster {}?",
+            state, from, to
+        );
+        if !(force || self.confirm(prompt)) {
+            return Ok(());
+        }
+
+        // There is no single owner update, so another cluster may claim the state
+        // in between unsetting and setting it, in which case it is not restored
+        self.api.unset_owner(&state).await?;
+        let err = match self.api.set_owner(&state, &to).await {
+            Ok(state) => return state.print(&self.stdout, self.json),
+            Err(err) => err,
+        };
+        let current = self.api.get_state(&state).await.with_context(|| {
+            format!(
+                "Transfer of state {} to cluster {} failed ({}) and its owner cannot be checked",
+                state, to, err
+            )
+        })?;
+        match current.owner.clone() {
+            Some(owner) if owner == to => current.print(&self.stdout, self.json),
+            Some(owner) => Err(err).context(format!(
+                "Cluster {} took ownership of state {} during the transfer from cluster {} to cluster {}",
+                owner, state, from, to
+            )),
+            None => {
+                self.inform(format_args!(
+                    "Restoring ownership of state {} to cluster {}",
+                    state, from
+                ))?;
+                self.api.set_owner(&state, &from).await.with_context(|| {
+                    format!(
+                        "State {} is left without owner, restoring cluster {} failed",
+                        state, from
+                    )
+                })?;
+                Err(err)
+            }
         }
     }
 
@@ -817,13 +2300,13 @@ impl StateHub {
     }
 
     async fn create_namespace(&self, namespace: String) -> anyhow::Result<()> {
-        k8s::validate_namespace(namespace)
+        k8s::validate_namespace(None, namespace)
             .await
             .map(|namespace| println!("{:#?}", namespace))
     }
 
     async fn save_cluster_token(&self, namespace: String, token: String) -> anyhow::Result<()> {
-        let secret = k8s::store_cluster_token(&namespace, &token).await?;
+        let secret = k8s::store_cluster_token(None, &namespace, &token).await?;
         if let Some(token) = k8s::extract_cluster_token(&secret) {
             log::debug!("Token: {}", token);
         }
@@ -835,24 +2318,68 @@ impl StateHub {
         namespace: String,
         cluster: v0::ClusterName,
         default_state: Option<String>,
+        settings: Vec<k8s::AgentSetting>,
     ) -> anyhow::Result<()> {
         let default_state = default_state.as_deref().unwrap_or("");
         let api = self.api.url("");
-        let _configmap = k8s::store_configmap(&namespace, &cluster, default_state, &api).await?;
+        let config =
+            k8s::ClusterAgentConfig::new(&cluster, default_state, &api).with_settings(&settings);
+        let _configmap = k8s::store_configmap(None, &namespace, &config).await?;
 
         Ok(())
     }
 
-    async fn list_regions(&self, zone: bool) -> anyhow::Result<()> {
-        k8s::get_regions(zone)
-            .await
-            .map(|map| {
-                map.into_iter()
-                    .map(|(key, value)| (key.unwrap_or_default(), value))
-                    .collect::<HashMap<_, _>>()
-            })
-            .map(Output::from)
-            .print(&self.stdout, self.json)
+    async fn list_regions(&self, contexts: Vec<String>, zone: bool) -> anyhow::Result<()> {
+        if contexts.is_empty() {
+            k8s::get_regions(None, zone)
+                .await
+                .map(collect_regions)
+                .map(Output::from)
+                .print(&self.stdout, self.json)
+        } else {
+            let regions = contexts
+                .iter()
+                .map(|context| k8s::get_regions(Some(context), zone));
+            let regions = future::join_all(regions).await;
+
+            // Unreachable contexts are reported, the rest are listed anyway
+            let mut found = HashMap::new();
+            let mut results = vec![];
+            for (context, result) in contexts.into_iter().zip(regions) {
+                match result {
+                    Ok(regions) => {
+                        found.insert(context, collect_regions(regions));
+                    }
+                    Err(err) => results.push((context, Err(err))),
+                }
+            }
+            Output::from(found).print(&self.stdout, self.json)?;
+            self.report_results(results)
+        }
+    }
+
+    async fn regions(&self, vendor: Option<v0::Vendor>) -> anyhow::Result<()> {
+        let regions = match self.api.get_regions().await {
+            Ok(regions) => regions.into_inner(),
+            Err(err) => {
+                self.verbosely(format_args!(
+                    "Using built-in region list, cannot fetch it from statehub: {}",
+                    err
+                ))?;
+                v0::Region::builtin()
+            }
+        };
+        let regions = regions
+            .into_iter()
+            .filter(|region| vendor.iter().all(|vendor| region.vendor == *vendor))
+            .collect::<Vec<_>>();
+        if let Some(vendor) = vendor.filter(|_| regions.is_empty()) {
+            anyhow::bail!(
+                "Cloud vendor {} is not supported yet, regions are available for aws and azure",
+                vendor
+            );
+        }
+        Output::from(regions).print(&self.stdout, self.json)
     }
 
     async fn list_namespaces(&self) -> anyhow::Result<()> {
@@ -930,3 +2457,27 @@ impl StateHub {
             .interact_text_on(&self.stdout)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_system_without_storage_class() {
+        let state = v0::State::new("alfa");
+        let fs = volume_file_system(&state, Some(v0::VolumeFileSystem::Jfs)).unwrap();
+        assert_eq!(fs, v0::VolumeFileSystem::Jfs);
+        let fs = volume_file_system(&state, None).unwrap();
+        assert_eq!(fs, v0::VolumeFileSystem::Ext4);
+        assert!(volume_file_system(&state, Some(v0::VolumeFileSystem::Fat)).is_err());
+    }
+
+    #[test]
+    fn file_system_of_storage_class() {
+        let mut state = v0::State::new("alfa");
+        state.storage_class = Some(v0::StorageClass::new("alfa"));
+        let fs = volume_file_system(&state, None).unwrap();
+        assert_eq!(fs, v0::VolumeFileSystem::Ext4);
+        assert!(volume_file_system(&state, Some(v0::VolumeFileSystem::Jfs)).is_err());
+    }
+}
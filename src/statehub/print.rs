@@ -65,14 +65,24 @@ impl Print for StateAndClusters {
     }
 }
 
+impl Print for VolumeAndClusters {
+    fn print(self, stdout: &Term, json: bool) -> anyhow::Result<()> {
+        let text = if json {
+            json::to_string(&self)?
+        } else {
+            self.detailed_show()
+        };
+        stdout.write_line(&text)?;
+        Ok(())
+    }
+}
+
 impl Print for ClusterAndStates {
     fn print(self, stdout: &Term, json: bool) -> anyhow::Result<()> {
         let text = if json {
             json::to_string(&self)?
         } else {
-            let cluster = self.cluster.detailed_show();
-            let states = self.states.iter().map(ToString::to_string).join(" ");
-            format!("{}\nVisible states:\n    {}", cluster, states)
+            self.detailed_show()
         };
         stdout.write_line(&text)?;
         Ok(())
@@ -84,6 +94,8 @@ pub(super) struct ClusterAndStates {
     #[serde(flatten)]
     cluster: v0::Cluster,
     states: Vec<v0::StateName>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
 }
 
 impl ClusterAndStates {
@@ -104,7 +116,16 @@ impl ClusterAndStates {
             .map(|state| state.name)
             .collect();
 
-        Self { cluster, states }
+        Self {
+            cluster,
+            states,
+            context: None,
+        }
+    }
+
+    /// Kubeconfig context the cluster was picked by
+    pub(super) fn with_context(self, context: Option<String>) -> Self {
+        Self { context, ..self }
     }
 }
 
@@ -115,8 +136,13 @@ impl Show for ClusterAndStates {
 
     fn detailed_show(&self) -> String {
         let cluster = self.cluster.detailed_show();
+        let context = self
+            .context
+            .as_ref()
+            .map(|context| format!("\nKubeconfig context: {}", context))
+            .unwrap_or_default();
         let states = self.states.iter().map(ToString::to_string).join(" ");
-        format!("{}\nVisible states:\n    {}", cluster, states)
+        format!("{}{}\nVisible states:\n    {}", cluster, context, states)
     }
 }
 
@@ -160,3 +186,93 @@ impl Show for StateAndClusters {
         format!("{}\nVisible clusters:\n    {}", state, clusters)
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct VolumeAndClusters {
+    #[serde(flatten)]
+    volume: v0::Volume,
+    mountable_clusters: Vec<v0::ClusterName>,
+}
+
+impl VolumeAndClusters {
+    /// Clusters allowed to use the state, that have the volume in any of their locations
+    pub(super) fn new(
+        volume: Detailed<Output<v0::Volume>>,
+        state: &v0::State,
+        clusters: Output<Vec<v0::Cluster>>,
+    ) -> Self {
+        let volume = volume.0.into_inner();
+        let volume_locations = volume
+            .locations
+            .iter()
+            .filter_map(v0::VolumeLocation::location)
+            .collect::<Vec<_>>();
+        let mountable_clusters = clusters
+            .into_iter()
+            .filter(|cluster| state.is_cluster_allowed(&cluster.name))
+            .filter(|cluster| {
+                cluster
+                    .all_locations()
+                    .iter()
+                    .any(|location| volume_locations.contains(location))
+            })
+            .map(|cluster| cluster.name)
+            .collect();
+
+        Self {
+            volume,
+            mountable_clusters,
+        }
+    }
+}
+
+impl Show for VolumeAndClusters {
+    fn show(&self) -> String {
+        self.detailed_show()
+    }
+
+    fn detailed_show(&self) -> String {
+        let volume = self.volume.detailed_show();
+        let clusters = self
+            .mountable_clusters
+            .iter()
+            .map(ToString::to_string)
+            .join(" ");
+        format!("{}\nMountable clusters:\n    {}", volume, clusters)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct VolumeAndConsumers {
+    #[serde(flatten)]
+    volume: v0::Volume,
+    consumers: Vec<k8s::VolumeConsumer>,
+}
+
+impl VolumeAndConsumers {
+    pub(super) fn new(volume: v0::Volume, consumers: &[k8s::VolumeConsumer]) -> Self {
+        let consumers = consumers
+            .iter()
+            .filter(|consumer| consumer.volume == volume.name)
+            .cloned()
+            .collect();
+        Self { volume, consumers }
+    }
+}
+
+impl Show for VolumeAndConsumers {
+    fn show(&self) -> String {
+        let volume = self.volume.show();
+        let consumers = self
+            .consumers
+            .iter()
+            .map(|consumer| format!("\n    {}", consumer.show()));
+        format!("{}{}", volume, consumers.format(""))
+    }
+}
+
+impl Show for Vec<VolumeAndConsumers> {
+    fn show(&self) -> String {
+        self.iter().map(Show::show).join("\n")
+    }
+}
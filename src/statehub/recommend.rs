@@ -0,0 +1,167 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::cmp::Ordering;
+
+use super::*;
+
+/// Why the location is recommended, in order of preference
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum Reason {
+    /// Closest region of the other cloud vendor, survives vendor wide outage at low latency
+    CrossCloudPair,
+    /// Another region of the same vendor, survives regional outage
+    SameCloud,
+    /// Any other region of the other vendor
+    CrossCloud,
+}
+
+/// Location proposed for the state replica of the cluster
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Recommendation {
+    location: String,
+    display_name: String,
+    reason: Reason,
+    near: String,
+    distance_km: u64,
+}
+
+impl Reason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::CrossCloudPair => "cross-cloud pair",
+            Self::SameCloud => "same cloud",
+            Self::CrossCloud => "cross-cloud",
+        }
+    }
+}
+
+impl Show for Recommendation {
+    fn show(&self) -> String {
+        format!(
+            "{:<24} {:<28} {}, ~{} km from {}",
+            self.location,
+            self.display_name,
+            self.reason.as_str(),
+            self.distance_km,
+            self.near
+        )
+    }
+}
+
+impl Show for Vec<Recommendation> {
+    fn show(&self) -> String {
+        self.iter().map(Show::show).join("\n")
+    }
+}
+
+impl StateHub {
+    pub(super) async fn recommend_locations(
+        &self,
+        cluster: v0::ClusterName,
+        count: usize,
+    ) -> anyhow::Result<()> {
+        let locations = self.api.get_cluster(&cluster).await?.all_locations();
+        anyhow::ensure!(
+            !locations.is_empty(),
+            "Cluster {} has no known locations",
+            cluster
+        );
+        self.verbosely(format_args!(
+            "Cluster {} is in {}",
+            cluster,
+            locations.show()
+        ))?;
+
+        Output::from(recommend(&locations, count)).print(&self.stdout, self.json)
+    }
+}
+
+/// Closest other vendor regions pair with the cluster locations first,
+/// then the rest of the regions follow by reason and distance
+fn recommend(locations: &[Location], count: usize) -> Vec<Recommendation> {
+    let all = Location::all();
+    let candidates = all.iter().filter(|location| !locations.contains(location));
+
+    let pairs = locations
+        .iter()
+        .filter_map(|location| {
+            candidates
+                .clone()
+                .filter(|candidate| !candidate.is_same_vendor(location))
+                .min_by(|a, b| by_distance(location, a, b))
+        })
+        .collect::<Vec<_>>();
+
+    let mut recommendations = candidates
+        .map(|candidate| {
+            let near = locations
+                .iter()
+                .min_by(|a, b| by_distance(candidate, a, b))
+                .expect("cluster locations are not empty");
+            let reason = if pairs.contains(&candidate) {
+                Reason::CrossCloudPair
+            } else if candidate.is_same_vendor(near) {
+                Reason::SameCloud
+            } else {
+                Reason::CrossCloud
+            };
+            Recommendation {
+                location: format!("{:#}", candidate),
+                display_name: candidate.display_name().to_string(),
+                reason,
+                near: format!("{:#}", near),
+                distance_km: candidate.distance_km(near).round() as u64,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    recommendations
+        .sort_by_key(|recommendation| (recommendation.reason, recommendation.distance_km));
+    recommendations.truncate(count);
+    recommendations
+}
+
+fn by_distance(from: &Location, a: &Location, b: &Location) -> Ordering {
+    from.distance_km(a)
+        .partial_cmp(&from.distance_km(b))
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locations(recommendations: &[Recommendation]) -> Vec<&str> {
+        recommendations
+            .iter()
+            .map(|recommendation| recommendation.location.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn single_location() {
+        let location = "aws/us-east-1".parse::<Location>().unwrap();
+        let recommendations = recommend(&[location], 2);
+        assert_eq!(recommendations[0].reason, Reason::CrossCloudPair);
+        assert!(recommendations[0].location.starts_with("azure/eastus"));
+        assert_eq!(recommendations[1].reason, Reason::SameCloud);
+        assert_eq!(recommendations[1].location, "aws/us-east-2");
+    }
+
+    #[test]
+    fn cluster_locations_are_excluded() {
+        let cluster = ["aws/eu-west-1", "azure/northeurope"]
+            .iter()
+            .map(|location| location.parse::<Location>().unwrap())
+            .collect::<Vec<_>>();
+        let recommendations = recommend(&cluster, 100);
+        assert_eq!(recommendations.len(), Location::all().len() - cluster.len());
+        assert!(!locations(&recommendations).contains(&"aws/eu-west-1"));
+        assert!(!locations(&recommendations).contains(&"azure/northeurope"));
+    }
+}
@@ -0,0 +1,264 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::future::Future;
+use std::str;
+
+use futures::stream::{self, StreamExt};
+
+use super::*;
+
+const NAME: &str = "name";
+
+/// Objects picked for a bulk operation instead of a single named one
+#[derive(Debug, StructOpt)]
+pub(super) struct Selection {
+    #[structopt(
+        help = "Select objects by name and labels, e.g. 'name=prod-*,team=storage'",
+        long,
+        short = "l",
+        conflicts_with = "all"
+    )]
+    selector: Option<Selector>,
+    #[structopt(help = "Select all objects", long)]
+    all: bool,
+    #[structopt(
+        help = "Maximum number of objects processed concurrently",
+        long,
+        default_value = "4"
+    )]
+    parallel: usize,
+    #[structopt(help = "Do not ask for confirmation of bulk operation", long, short)]
+    yes: bool,
+}
+
+/// Comma separated terms, all of which have to match:
+/// `name=glob` for object name, `key=glob` for label value, or just `key` for label presence
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Selector {
+    name: Option<String>,
+    labels: Vec<(String, Option<String>)>,
+}
+
+impl Selection {
+    fn matches(&self, name: &str, labels: &v0::Labels) -> bool {
+        self.all
+            || self
+                .selector
+                .iter()
+                .any(|selector| selector.matches(name, labels))
+    }
+}
+
+impl Selector {
+    pub(super) fn matches(&self, name: &str, labels: &v0::Labels) -> bool {
+        self.name.iter().all(|glob| glob_match(glob, name))
+            && self
+                .labels
+                .iter()
+                .all(|(key, glob)| match (labels.get(key), glob) {
+                    (Some(value), Some(glob)) => glob_match(glob, value),
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                })
+    }
+}
+
+impl str::FromStr for Selector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut selector = Self::default();
+        for term in s.split(',').map(str::trim) {
+            let (key, value) = match term.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+                None => (term, None),
+            };
+            match (key, value) {
+                (NAME, Some(glob)) => selector.name = Some(glob),
+                (NAME, None) => anyhow::bail!("Selector {} needs a value", NAME),
+                (key, value) => {
+                    v0::validate_label_key(key).map_err(|reason| {
+                        anyhow::anyhow!("Invalid selector {}: {}", term, reason)
+                    })?;
+                    selector.labels.push((key.to_string(), value));
+                }
+            }
+        }
+        Ok(selector)
+    }
+}
+
+impl StateHub {
+    /// Run the operation on the named state or on every selected one
+    pub(super) async fn for_states<F, Fut>(
+        &self,
+        state: Option<v0::StateName>,
+        selection: &Selection,
+        operation: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn(v0::StateName) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        if let Some(state) = state {
+            return operation(state).await;
+        }
+
+        let states = self
+            .api
+            .get_all_states()
+            .await?
+            .into_iter()
+            .filter(|state| selection.matches(state.name.as_ref(), &state.metadata.labels))
+            .map(|state| state.name)
+            .collect();
+        self.bulk_helper(states, selection, operation).await
+    }
+
+    /// Run the operation on the named volume or on every selected volume of the state
+    pub(super) async fn for_volumes<F, Fut>(
+        &self,
+        state: &v0::StateName,
+        volume: Option<v0::VolumeName>,
+        selection: &Selection,
+        operation: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn(v0::VolumeName) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        if let Some(volume) = volume {
+            return operation(volume).await;
+        }
+
+        let volumes = self
+            .api
+            .get_all_volumes(state.clone())
+            .await?
+            .into_iter()
+            .filter(|volume| selection.matches(volume.name.as_ref(), &volume.metadata.labels))
+            .map(|volume| volume.name)
+            .collect();
+        self.bulk_helper(volumes, selection, operation).await
+    }
+
+    /// Selected objects are listed and confirmed once before the operation runs on them
+    async fn bulk_helper<T, F, Fut>(
+        &self,
+        items: Vec<T>,
+        selection: &Selection,
+        operation: F,
+    ) -> anyhow::Result<()>
+    where
+        T: Clone + fmt::Display,
+        F: Fn(T) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        if items.is_empty() {
+            return self
+                .inform("Nothing matches the selection")
+                .map_err(Into::into);
+        }
+
+        self.inform(format_args!("Selected: {}", items.iter().join(", ")))?;
+        let prompt = format!(
+            "Operation will run on {} selected object(s). Are you sure?",
+            items.len()
+        );
+        if !(selection.yes || self.confirm(prompt)) {
+            return Ok(());
+        }
+
+        let results = stream::iter(items)
+            .map(|item| {
+                let result = operation(item.clone());
+                async move { (item, result.await) }
+            })
+            .buffered(selection.parallel.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        for (item, _) in results.iter().filter(|(_, result)| result.is_ok()) {
+            self.inform(format_args!("{}: done", item))?;
+        }
+        self.report_results(results)
+    }
+}
+
+/// Shell style glob with `*` and `?` wildcards
+fn glob_match(glob: &str, text: &str) -> bool {
+    let glob = glob.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut g, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, t));
+                g += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    g = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("prod-*", "prod-eu"));
+        assert!(glob_match("prod-*", "prod-"));
+        assert!(glob_match("*-db-?", "prod-db-1"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("prod-*", "staging-eu"));
+        assert!(!glob_match("*-db-?", "prod-db-10"));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn selector() {
+        let none = v0::Labels::new();
+        let selector = "name=prod-*".parse::<Selector>().unwrap();
+        assert!(selector.matches("prod-eu", &none));
+        assert!(!selector.matches("dev-eu", &none));
+        assert!("prod-*".parse::<Selector>().is_err());
+        assert!("name".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn label_selector() {
+        let labels = ["env=prod", "team=storage"]
+            .iter()
+            .map(|label| label.parse::<v0::Label>().unwrap());
+        let labels = v0::Metadata::new(labels, None).labels;
+        let selector = "name=alfa-*,env=prod".parse::<Selector>().unwrap();
+        assert!(selector.matches("alfa-eu", &labels));
+        assert!(!selector.matches("bravo-eu", &labels));
+        assert!("team".parse::<Selector>().unwrap().matches("x", &labels));
+        assert!(!"owner".parse::<Selector>().unwrap().matches("x", &labels));
+        assert!(!"env=dev*"
+            .parse::<Selector>()
+            .unwrap()
+            .matches("x", &labels));
+    }
+}
@@ -0,0 +1,276 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod};
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ClusterStatus {
+    cluster: v0::ClusterName,
+    context: Option<String>,
+    namespace: String,
+    pods: Vec<PodStatus>,
+    charts: Vec<ChartStatus>,
+    token: Option<TokenStatus>,
+    configmap: Option<BTreeMap<String, String>>,
+    claims: Vec<ClaimStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PodStatus {
+    name: String,
+    phase: String,
+    ready: usize,
+    containers: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ChartStatus {
+    chart: String,
+    expected: String,
+    installed: Option<k8s::HelmRelease>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenStatus {
+    created: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ClaimStatus {
+    namespace: String,
+    name: String,
+    storage_class: String,
+    phase: String,
+    capacity: Option<String>,
+}
+
+impl StateHub {
+    pub(super) async fn cluster_status(
+        &self,
+        name: v0::ClusterName,
+        context: Option<String>,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let cluster = self.api.get_cluster(&name).await?.into_inner();
+        let context = self.cluster_context_helper(&name, context)?;
+        let kube_context = context.as_deref();
+
+        let pods = k8s::get_pods(kube_context, &namespace)
+            .await?
+            .into_iter()
+            .map(PodStatus::from)
+            .collect();
+
+        let helm = k8s::Helm::new(namespace.clone(), None, true).with_context(context.clone());
+        let mut charts = vec![];
+        if k8s::helm_is_found() {
+            for chart in &cluster.helm {
+                let installed = helm.release(chart).await?;
+                let expected = chart.version.clone();
+                let chart = chart.chart.clone();
+                charts.push(ChartStatus {
+                    chart,
+                    expected,
+                    installed,
+                });
+            }
+        } else {
+            log::warn!("helm is not detected, skipping installed chart versions");
+        }
+
+        let token = k8s::get_cluster_token(kube_context, &namespace)
+            .await?
+            .map(|secret| TokenStatus {
+                created: secret.metadata.creation_timestamp.map(|time| time.0),
+            });
+
+        let configmap = k8s::get_configmap(kube_context, &namespace)
+            .await?
+            .map(|configmap| configmap.data);
+
+        let storage_classes = self
+            .api
+            .get_all_states()
+            .await?
+            .into_iter()
+            .map(|state| state.storage_class_name())
+            .collect::<HashSet<_>>();
+        let claims = k8s::get_persistent_volume_claims(kube_context)
+            .await?
+            .into_iter()
+            .filter_map(ClaimStatus::from_claim)
+            .filter(|claim| storage_classes.contains(&claim.storage_class))
+            .collect();
+
+        Output::from(ClusterStatus {
+            cluster: name,
+            context,
+            namespace,
+            pods,
+            charts,
+            token,
+            configmap,
+            claims,
+        })
+        .print(&self.stdout, self.json)
+    }
+
+    /// Kubeconfig context to reach the given cluster, `None` stands for current context
+    pub(super) fn cluster_context_helper(
+        &self,
+        cluster: &v0::ClusterName,
+        context: Option<String>,
+    ) -> anyhow::Result<Option<String>> {
+        if let Some(context) = context {
+            k8s::resolve_contexts(vec![context], false).map(|mut contexts| contexts.pop())
+        } else if let Some(context) = k8s::find_context(cluster) {
+            Ok(Some(context))
+        } else if k8s::get_current_cluster_name().as_ref() == Some(cluster) {
+            Ok(None)
+        } else {
+            anyhow::bail!(
+                "No kubeconfig context found for cluster {}, use '--context'",
+                cluster
+            )
+        }
+    }
+}
+
+impl From<Pod> for PodStatus {
+    fn from(pod: Pod) -> Self {
+        let name = pod.metadata.name.unwrap_or_default();
+        let status = pod.status.unwrap_or_default();
+        let phase = status.phase.unwrap_or_default();
+        let containers = status.container_statuses.len();
+        let ready = status
+            .container_statuses
+            .iter()
+            .filter(|container| container.ready)
+            .count();
+        Self {
+            name,
+            phase,
+            ready,
+            containers,
+        }
+    }
+}
+
+impl ClaimStatus {
+    fn from_claim(claim: PersistentVolumeClaim) -> Option<Self> {
+        let storage_class = claim.spec?.storage_class_name?;
+        let namespace = claim.metadata.namespace.unwrap_or_default();
+        let name = claim.metadata.name.unwrap_or_default();
+        let status = claim.status.unwrap_or_default();
+        let phase = status.phase.unwrap_or_default();
+        let capacity = status
+            .capacity
+            .get("storage")
+            .map(|quantity| quantity.0.clone());
+        Some(Self {
+            namespace,
+            name,
+            storage_class,
+            phase,
+            capacity,
+        })
+    }
+}
+
+impl Show for PodStatus {
+    fn show(&self) -> String {
+        format!(
+            "  {:<48} {:<10} {}/{} ready",
+            self.name, self.phase, self.ready, self.containers
+        )
+    }
+}
+
+impl Show for ChartStatus {
+    fn show(&self) -> String {
+        let installed = self.installed.as_ref().map_or_else(
+            || String::from("not installed"),
+            |release| format!("{} ({})", release.version(), release.status),
+        );
+        format!(
+            "  {:<24} installed: {}, expected: {}",
+            self.chart, installed, self.expected
+        )
+    }
+}
+
+impl Show for TokenStatus {
+    fn show(&self) -> String {
+        self.created.map_or_else(
+            || String::from("present"),
+            |created| format!("created {}", HumanTime::from(created)),
+        )
+    }
+}
+
+impl Show for ClaimStatus {
+    fn show(&self) -> String {
+        format!(
+            "  {}/{:<32} {:<8} {:>8} {}",
+            self.namespace,
+            self.name,
+            self.phase,
+            self.capacity.as_deref().unwrap_or_default(),
+            self.storage_class
+        )
+    }
+}
+
+impl Show for ClusterStatus {
+    fn show(&self) -> String {
+        let none = || String::from("  None");
+        let section = |items: Vec<String>| {
+            if items.is_empty() {
+                none()
+            } else {
+                items.join("\n")
+            }
+        };
+        let configmap = self.configmap.as_ref().map_or_else(none, |data| {
+            data.iter()
+                .map(|(key, value)| format!("  {}: {}", key, value))
+                .join("\n")
+        });
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            format_args!("Cluster:     {}", self.cluster),
+            format_args!(
+                "Context:     {}",
+                self.context.as_deref().unwrap_or("current")
+            ),
+            format_args!("Namespace:   {}", self.namespace),
+            format_args!(
+                "Token:       {}",
+                self.token
+                    .as_ref()
+                    .map_or_else(|| String::from("missing"), Show::show)
+            ),
+            format_args!(
+                "Pods:\n{}",
+                section(self.pods.iter().map(Show::show).collect())
+            ),
+            format_args!(
+                "Helm charts:\n{}",
+                section(self.charts.iter().map(Show::show).collect())
+            ),
+            format_args!("Configmap:\n{}", configmap),
+            format_args!(
+                "Volume claims:\n{}",
+                section(self.claims.iter().map(Show::show).collect())
+            ),
+        )
+    }
+}
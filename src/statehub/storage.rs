@@ -0,0 +1,67 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use super::*;
+
+impl StateHub {
+    pub(super) async fn apply_storage_classes(
+        &self,
+        name: v0::ClusterName,
+        states: Vec<v0::StateName>,
+        context: Option<String>,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let context = self.cluster_context_helper(&name, context)?;
+        let context = context.as_deref();
+
+        for state in states {
+            let storage_class = self.api.get_state(&state).await?.storage_class();
+            let change = k8s::apply_storage_class(context, &state, &storage_class, force).await?;
+            self.inform(format_args!(
+                "Storage class {} for state {} {}",
+                storage_class.name, state, change
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) async fn set_default_storage_class(
+        &self,
+        name: v0::ClusterName,
+        state: Option<v0::StateName>,
+        context: Option<String>,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let context = self.cluster_context_helper(&name, context)?;
+        let context = context.as_deref();
+
+        let storage_class = if let Some(ref state) = state {
+            Some(self.api.get_state(state).await?.storage_class_name())
+        } else {
+            None
+        };
+
+        let changed = k8s::set_default_storage_class(context, storage_class.as_deref()).await?;
+        for storage_class in changed {
+            self.verbosely(format_args!("Updated storage class {}", storage_class))?;
+        }
+
+        // Keep statehub configmap in line, so that it does not set it back
+        if let Some(configmap) = k8s::get_configmap(context, &namespace).await? {
+            let default_state = state.as_ref().map(ToString::to_string).unwrap_or_default();
+            let mut config = k8s::ClusterAgentConfig::from_data(&configmap.data)?;
+            config.set(k8s::AgentSetting::DefaultState(default_state));
+            k8s::store_configmap(context, &namespace, &config).await?;
+        }
+
+        if let Some(storage_class) = storage_class {
+            self.inform(format_args!("Default storage class is {}", storage_class))
+        } else {
+            self.inform("Default storage class is cleared")
+        }
+        .map_err(anyhow::Error::from)
+    }
+}
@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use k8s_openapi::api::core::v1::Secret;
+use sha2::{Digest, Sha256};
+
+use super::*;
+
+/// What is safe to tell about a cluster token - never the token itself
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ClusterTokenInfo {
+    cluster: v0::ClusterName,
+    fingerprint: Option<String>,
+    issued: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    restarted: Vec<String>,
+}
+
+impl StateHub {
+    pub(super) async fn rotate_cluster_token(
+        &self,
+        name: v0::ClusterName,
+        context: Option<String>,
+        namespace: String,
+        restart: bool,
+    ) -> anyhow::Result<()> {
+        let context = self.cluster_context_helper(&name, context)?;
+        let context = context.as_deref();
+        let token = self.api.issue_cluster_token(&name).await?;
+        self.verbosely(format_args!("Issued new token for {}", name))?;
+
+        let secret = k8s::rotate_cluster_token(context, &namespace, &token.token).await?;
+
+        let restarted = if restart {
+            k8s::restart_workloads(context, &namespace).await?
+        } else {
+            vec![]
+        };
+
+        Output::from(ClusterTokenInfo::new(name, &secret, restarted)).print(&self.stdout, self.json)
+    }
+
+    pub(super) async fn show_cluster_token(
+        &self,
+        name: v0::ClusterName,
+        context: Option<String>,
+        namespace: String,
+    ) -> anyhow::Result<()> {
+        let context = self.cluster_context_helper(&name, context)?;
+        let secret = k8s::get_cluster_token(context.as_deref(), &namespace)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!("No cluster token secret found in namespace {}", namespace)
+            })?;
+
+        Output::from(ClusterTokenInfo::new(name, &secret, vec![])).print(&self.stdout, self.json)
+    }
+
+    pub(super) async fn revoke_cluster_token(
+        &self,
+        name: v0::ClusterName,
+        context: Option<String>,
+        namespace: String,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let prompt = format!(
+            "Cluster {} will not be able to access statehub until a new token is issued. Are you sure?",
+            name
+        );
+        if !(force || self.confirm(prompt)) {
+            return Ok(());
+        }
+
+        self.api.revoke_cluster_token(&name).await?;
+        self.inform(format_args!("Revoked cluster token for {}", name))?;
+
+        match self.cluster_context_helper(&name, context) {
+            Ok(context) => {
+                if k8s::delete_cluster_token(context.as_deref(), &namespace).await? {
+                    self.verbosely(format_args!(
+                        "Deleted cluster token secret from namespace {}",
+                        namespace
+                    ))?;
+                }
+            }
+            Err(err) => log::warn!("Cluster token secret is left in place: {}", err),
+        }
+
+        Ok(())
+    }
+}
+
+impl ClusterTokenInfo {
+    fn new(cluster: v0::ClusterName, secret: &Secret, restarted: Vec<String>) -> Self {
+        let fingerprint = k8s::extract_cluster_token(secret).map(|token| fingerprint(&token));
+        let issued = k8s::cluster_token_issued(secret);
+        Self {
+            cluster,
+            fingerprint,
+            issued,
+            restarted,
+        }
+    }
+}
+
+/// Short SHA-256 based token fingerprint, good enough to compare tokens
+fn fingerprint(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    let hex = digest
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .join(":");
+    format!("SHA256:{}", hex)
+}
+
+impl Show for ClusterTokenInfo {
+    fn show(&self) -> String {
+        let fingerprint = self.fingerprint.as_deref().unwrap_or("missing");
+        let issued = self.issued.map_or_else(
+            || String::from("unknown"),
+            |issued| HumanTime::from(issued).to_string(),
+        );
+        let mut lines = vec![
+            format!("Cluster:     {}", self.cluster),
+            format!("Fingerprint: {}", fingerprint),
+            format!("Issued:      {}", issued),
+        ];
+        if !self.restarted.is_empty() {
+            lines.push(format!("Restarted:   {}", self.restarted.join(", ")));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn fingerprint() {
+        let fingerprint = super::fingerprint("token");
+        assert!(fingerprint.starts_with("SHA256:"));
+        assert_eq!(fingerprint.len(), "SHA256:".len() + 8 * 3 - 1);
+        assert_eq!(fingerprint, super::fingerprint("token"));
+        assert_ne!(fingerprint, super::fingerprint("other"));
+    }
+}
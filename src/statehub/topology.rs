@@ -0,0 +1,523 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::str;
+
+use console::{measure_text_width, pad_str, Alignment};
+
+use super::*;
+
+const UNATTACHED: &str = "Clusters not using any state:";
+
+/// How the topology is rendered
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum TopologyFormat {
+    Tree,
+    Matrix,
+    Dot,
+    Mermaid,
+}
+
+/// Whole account as states, their locations and clusters using them there
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Topology {
+    states: Vec<StateNode>,
+    clusters: Vec<ClusterNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateNode {
+    name: v0::StateName,
+    condition: v0::Condition,
+    owner: Option<v0::ClusterName>,
+    locations: Vec<LocationNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationNode {
+    location: String,
+    status: v0::StateLocationStatus,
+    clusters: Vec<v0::ClusterName>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusterNode {
+    name: v0::ClusterName,
+    locations: Vec<String>,
+}
+
+impl str::FromStr for TopologyFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(Self::Tree),
+            "matrix" => Ok(Self::Matrix),
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            other => anyhow::bail!(
+                "Invalid topology format: {}, expected tree, matrix, dot or mermaid",
+                other
+            ),
+        }
+    }
+}
+
+impl Topology {
+    /// Cluster uses the state in a location when it is in that location and allowed to use the state
+    fn new(states: Vec<v0::State>, clusters: Vec<v0::Cluster>) -> Self {
+        let cluster_locations = clusters
+            .iter()
+            .map(|cluster| (cluster, cluster.all_locations()))
+            .collect::<Vec<_>>();
+
+        let states = states
+            .into_iter()
+            .map(|state| {
+                let locations = state
+                    .all_locations()
+                    .into_iter()
+                    .map(|location| LocationNode {
+                        location: format!("{:#}", location),
+                        status: state
+                            .locations
+                            .status(&location)
+                            .unwrap_or(v0::StateLocationStatus::Error),
+                        clusters: cluster_locations
+                            .iter()
+                            .filter(|(cluster, _)| state.is_cluster_allowed(&cluster.name))
+                            .filter(|(_, locations)| locations.contains(&location))
+                            .map(|(cluster, _)| cluster.name.clone())
+                            .collect(),
+                    })
+                    .collect();
+                StateNode {
+                    name: state.name,
+                    condition: state.condition,
+                    owner: state.owner,
+                    locations,
+                }
+            })
+            .collect();
+
+        let clusters = cluster_locations
+            .into_iter()
+            .map(|(cluster, locations)| ClusterNode {
+                name: cluster.name.clone(),
+                locations: locations
+                    .iter()
+                    .map(|location| format!("{:#}", location))
+                    .collect(),
+            })
+            .collect();
+
+        Self { states, clusters }
+    }
+
+    fn render(&self, format: TopologyFormat) -> String {
+        match format {
+            TopologyFormat::Tree => self.show(),
+            TopologyFormat::Matrix => self.matrix(),
+            TopologyFormat::Dot => self.dot(),
+            TopologyFormat::Mermaid => self.mermaid(),
+        }
+    }
+
+    fn unattached_clusters(&self) -> Vec<&v0::ClusterName> {
+        self.clusters
+            .iter()
+            .map(|cluster| &cluster.name)
+            .filter(|name| {
+                !self
+                    .states
+                    .iter()
+                    .flat_map(|state| state.locations.iter())
+                    .any(|location| location.clusters.contains(name))
+            })
+            .collect()
+    }
+
+    fn all_locations(&self) -> Vec<&str> {
+        self.states
+            .iter()
+            .flat_map(|state| state.locations.iter())
+            .map(|location| location.location.as_str())
+            .chain(
+                self.clusters
+                    .iter()
+                    .flat_map(|cluster| cluster.locations.iter())
+                    .map(String::as_str),
+            )
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Registered clusters followed by owners that are not registered clusters,
+    /// the latter get their own nodes as well
+    fn cluster_names(&self) -> Vec<&v0::ClusterName> {
+        self.clusters
+            .iter()
+            .map(|cluster| &cluster.name)
+            .chain(self.states.iter().filter_map(|state| state.owner.as_ref()))
+            .unique()
+            .collect()
+    }
+
+    /// States and clusters by locations, with status glyph where the state is and cluster glyph where the cluster is
+    fn matrix(&self) -> String {
+        let locations = self.all_locations();
+        let state_names = self
+            .states
+            .iter()
+            .map(|state| format!("{} {}", state.condition.show(), state.name))
+            .collect::<Vec<_>>();
+        let cluster_names = self
+            .clusters
+            .iter()
+            .map(|cluster| format!("{} {}", v0::Cluster::CLUSTER, cluster.name))
+            .collect::<Vec<_>>();
+        let name_width = state_names
+            .iter()
+            .chain(&cluster_names)
+            .map(|name| measure_text_width(name))
+            .max()
+            .unwrap_or_default();
+        let widths = locations
+            .iter()
+            .map(|location| location.len())
+            .collect::<Vec<_>>();
+
+        let header = locations
+            .iter()
+            .map(|location| location.to_string())
+            .collect::<Vec<_>>();
+        let mut rows = vec![row(&pad("", name_width), &header, &widths)];
+        for (state, name) in self.states.iter().zip(&state_names) {
+            let cells = locations
+                .iter()
+                .map(|location| {
+                    state
+                        .locations
+                        .iter()
+                        .find(|node| node.location == *location)
+                        .map(|node| node.status.show())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+            rows.push(row(&pad(name, name_width), &cells, &widths));
+        }
+        for (cluster, name) in self.clusters.iter().zip(&cluster_names) {
+            let cells = locations
+                .iter()
+                .map(|location| {
+                    if cluster.locations.iter().any(|other| other == location) {
+                        v0::Cluster::CLUSTER.to_string()
+                    } else {
+                        String::new()
+                    }
+                })
+                .collect::<Vec<_>>();
+            rows.push(row(&pad(name, name_width), &cells, &widths));
+        }
+
+        rows.join("\n")
+    }
+
+    /// Graphviz DOT graph, states point to their locations, clusters to locations they are in
+    fn dot(&self) -> String {
+        let mut lines = vec![
+            String::from("digraph statehub {"),
+            String::from("  rankdir=LR;"),
+        ];
+        for location in self.all_locations() {
+            lines.push(format!(
+                "  {} [label={:?}, shape=ellipse];",
+                dot_id("location", location),
+                location
+            ));
+        }
+        for state in &self.states {
+            let id = dot_id("state", state.name.as_ref());
+            lines.push(format!(
+                "  {} [label={:?}, shape=box, color={}];",
+                id,
+                state.name.as_ref(),
+                condition_color(state.condition)
+            ));
+            for location in &state.locations {
+                lines.push(format!(
+                    "  {} -> {} [label={:?}];",
+                    id,
+                    dot_id("location", &location.location),
+                    location.status.as_str()
+                ));
+            }
+            if let Some(ref owner) = state.owner {
+                lines.push(format!(
+                    "  {} -> {} [label=\"owner\", style=dashed];",
+                    dot_id("cluster", owner.as_ref()),
+                    id
+                ));
+            }
+        }
+        for name in self.cluster_names() {
+            lines.push(format!(
+                "  {} [label={:?}, shape=component];",
+                dot_id("cluster", name.as_ref()),
+                name.as_ref()
+            ));
+        }
+        for cluster in &self.clusters {
+            let id = dot_id("cluster", cluster.name.as_ref());
+            for location in &cluster.locations {
+                lines.push(format!("  {} -> {};", id, dot_id("location", location)));
+            }
+        }
+        lines.push(String::from("}"));
+        lines.join("\n")
+    }
+
+    /// Mermaid flowchart with the same nodes and edges as the DOT graph
+    fn mermaid(&self) -> String {
+        let locations = self.all_locations();
+        let location_id = |location: &str| {
+            let index = locations
+                .iter()
+                .position(|other| *other == location)
+                .unwrap_or_default();
+            format!("location{}", index)
+        };
+        let cluster_names = self.cluster_names();
+        let cluster_id = |cluster: &v0::ClusterName| {
+            let index = cluster_names
+                .iter()
+                .position(|other| *other == cluster)
+                .unwrap_or_default();
+            format!("cluster{}", index)
+        };
+
+        let mut lines = vec![String::from("graph LR")];
+        for location in &locations {
+            lines.push(format!("  {}((\"{}\"))", location_id(location), location));
+        }
+        for (index, state) in self.states.iter().enumerate() {
+            let id = format!("state{}", index);
+            lines.push(format!(
+                "  {}[\"{} ({})\"]",
+                id,
+                state.name,
+                condition_color(state.condition)
+            ));
+            for location in &state.locations {
+                lines.push(format!(
+                    "  {} -->|{}| {}",
+                    id,
+                    location.status.as_str(),
+                    location_id(&location.location)
+                ));
+            }
+            if let Some(ref owner) = state.owner {
+                lines.push(format!("  {} -.->|owner| {}", cluster_id(owner), id));
+            }
+        }
+        for name in &cluster_names {
+            lines.push(format!("  {}[[\"{}\"]]", cluster_id(name), name));
+        }
+        for cluster in &self.clusters {
+            let id = cluster_id(&cluster.name);
+            for location in &cluster.locations {
+                lines.push(format!("  {} --> {}", id, location_id(location)));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl Show for Topology {
+    /// States with their locations and clusters using them there as a tree
+    fn show(&self) -> String {
+        let mut lines = vec![];
+        for state in &self.states {
+            let owner = state
+                .owner
+                .as_ref()
+                .map(|owner| format!(" (owner {})", owner))
+                .unwrap_or_default();
+            lines.push(format!(
+                "{} {}{}",
+                state.condition.show(),
+                state.name,
+                owner
+            ));
+            for (index, location) in state.locations.iter().enumerate() {
+                let last = index + 1 == state.locations.len();
+                let (branch, indent) = if last {
+                    ("└──", "    ")
+                } else {
+                    ("├──", "│   ")
+                };
+                lines.push(format!(
+                    "{} {} {}",
+                    branch,
+                    location.location,
+                    location.status.show()
+                ));
+                for (index, cluster) in location.clusters.iter().enumerate() {
+                    let branch = if index + 1 == location.clusters.len() {
+                        "└──"
+                    } else {
+                        "├──"
+                    };
+                    lines.push(format!(
+                        "{}{} {} {}",
+                        indent,
+                        branch,
+                        v0::Cluster::CLUSTER,
+                        cluster
+                    ));
+                }
+            }
+        }
+
+        let unattached = self.unattached_clusters();
+        if !unattached.is_empty() {
+            lines.push(String::from(UNATTACHED));
+            for cluster in unattached {
+                lines.push(format!("    {} {}", v0::Cluster::CLUSTER, cluster));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl StateHub {
+    pub(super) async fn topology(&self, format: Option<TopologyFormat>) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !(self.json && format.is_some()),
+            "--format cannot be used together with --json"
+        );
+
+        let states = self.api.get_all_states().await?.into_inner();
+        let clusters = self.api.get_all_clusters().await?.into_inner();
+        let topology = Topology::new(states, clusters);
+        if self.json {
+            Output::from(topology).print(&self.stdout, self.json)
+        } else {
+            let format = format.unwrap_or(TopologyFormat::Tree);
+            self.stdout.write_line(&topology.render(format))?;
+            Ok(())
+        }
+    }
+}
+
+fn pad(text: &str, width: usize) -> String {
+    pad_str(text, width, Alignment::Left, None).to_string()
+}
+
+fn row(name: &str, cells: &[String], widths: &[usize]) -> String {
+    let cells = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| pad(cell, *width))
+        .join("  ");
+    format!("{}  {}", name, cells).trim_end().to_string()
+}
+
+fn dot_id(kind: &str, name: &str) -> String {
+    format!("{:?}", format!("{}:{}", kind, name))
+}
+
+fn condition_color(condition: v0::Condition) -> &'static str {
+    match condition {
+        v0::Condition::Green => "green",
+        v0::Condition::Yellow => "yellow",
+        v0::Condition::Red => "red",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::v0::fixtures;
+
+    use super::*;
+
+    fn cluster(name: &str) -> v0::Cluster {
+        fixtures::cluster(name, &["aws/eu-west-1"])
+    }
+
+    fn state() -> v0::State {
+        let mut state = fixtures::state(
+            "alfa",
+            &[
+                ("aws/eu-west-1", "ok"),
+                ("azure/northeurope", "provisioning"),
+            ],
+        );
+        state.owner = Some("prod".into());
+        state
+    }
+
+    #[test]
+    fn clusters_by_location() {
+        let topology = Topology::new(vec![state()], vec![cluster("prod")]);
+        let locations = &topology.states[0].locations;
+        assert_eq!(locations[0].location, "aws/eu-west-1");
+        assert_eq!(locations[0].clusters, [v0::ClusterName::from("prod")]);
+        assert!(locations[1].clusters.is_empty());
+        assert!(topology.unattached_clusters().is_empty());
+    }
+
+    #[test]
+    fn dot() {
+        let dot = Topology::new(vec![state()], vec![cluster("prod")]).dot();
+        assert!(dot.starts_with("digraph statehub {"));
+        assert!(
+            dot.contains(r#""state:alfa" -> "location:azure/northeurope" [label="provisioning"];"#)
+        );
+        assert!(dot.contains(r#""cluster:prod" -> "state:alfa" [label="owner", style=dashed];"#));
+        assert!(dot.ends_with('}'));
+    }
+
+    #[test]
+    fn dot_unregistered_owners() {
+        let mut other = state();
+        other.name = "bravo".into();
+        other.owner = Some("staging".into());
+        let dot = Topology::new(vec![state(), other], vec![cluster("prod")]).dot();
+        assert!(dot.contains(r#""cluster:prod" [label="prod", shape=component];"#));
+        assert!(dot.contains(r#""cluster:staging" [label="staging", shape=component];"#));
+        assert_eq!(dot.matches(r#""cluster:prod" [label="#).count(), 1);
+        assert!(
+            dot.contains(r#""cluster:staging" -> "state:bravo" [label="owner", style=dashed];"#)
+        );
+    }
+
+    #[test]
+    fn mermaid() {
+        let mermaid = Topology::new(vec![state()], vec![cluster("prod")]).mermaid();
+        assert!(mermaid.starts_with("graph LR"));
+        assert!(mermaid.contains("state0 -->|ok| location0"));
+        assert!(mermaid.contains("cluster0 -.->|owner| state0"));
+        assert!(mermaid.contains("cluster0 --> location0"));
+    }
+
+    #[test]
+    fn mermaid_unregistered_owners() {
+        let mut other = state();
+        other.name = "bravo".into();
+        other.owner = Some("staging".into());
+        let mermaid = Topology::new(vec![state(), other], vec![]).mermaid();
+        assert!(mermaid.contains(r#"cluster0[["prod"]]"#));
+        assert!(mermaid.contains(r#"cluster1[["staging"]]"#));
+        assert!(mermaid.contains("cluster0 -.->|owner| state0"));
+        assert!(mermaid.contains("cluster1 -.->|owner| state1"));
+    }
+}
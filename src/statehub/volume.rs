@@ -0,0 +1,109 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use super::*;
+
+impl StateHub {
+    pub(super) async fn show_volume(
+        &self,
+        state: v0::StateName,
+        volume: v0::VolumeName,
+    ) -> anyhow::Result<()> {
+        let volume = self.api.get_volume(&state, &volume).await.map(Detailed)?;
+        let state = self.api.get_state(&state).await?;
+        if let Ok(clusters) = self.api.get_all_clusters().await {
+            VolumeAndClusters::new(volume, &state, clusters).print(&self.stdout, self.json)
+        } else {
+            volume.print(&self.stdout, self.json)
+        }
+    }
+
+    pub(super) async fn volume_consumers(
+        &self,
+        state: v0::StateName,
+        volume: v0::VolumeName,
+    ) -> anyhow::Result<()> {
+        let volume = self.api.get_volume(&state, &volume).await?.into_inner();
+        let consumers =
+            k8s::get_volume_consumers(None, &state, std::slice::from_ref(&volume)).await?;
+        if consumers.is_empty() {
+            self.verbosely(format_args!(
+                "Volume {} is not used in the current cluster",
+                volume.name
+            ))?;
+        }
+        Output::from(consumers).print(&self.stdout, self.json)
+    }
+
+    pub(super) async fn resize_volume(
+        &self,
+        state: v0::StateName,
+        volume: v0::VolumeName,
+        size: v0::VolumeSize,
+        wait: bool,
+        patch_pvc: bool,
+    ) -> anyhow::Result<()> {
+        let current = self.api.get_volume(&state, &volume).await?.into_inner();
+        let size_gi = size.size_gi();
+        anyhow::ensure!(
+            size_gi >= current.size_gi,
+            "Volume {} is {}, shrinking it to {} is not supported",
+            current.name,
+            v0::VolumeSize::from_gi(current.size_gi),
+            size
+        );
+        if !size.is_whole_gi() {
+            self.verbosely(format_args!(
+                "Volume size {} is rounded up to {}Gi",
+                size, size_gi
+            ))?;
+        }
+
+        let volume = if size_gi == current.size_gi {
+            self.verbosely(format_args!(
+                "Volume {} is already {}",
+                current.name,
+                v0::VolumeSize::from_gi(size_gi)
+            ))?;
+            current
+        } else {
+            let resize = v0::ResizeVolumeDto { size_gi };
+            self.api
+                .resize_volume(&state, &volume, resize)
+                .await?
+                .into_inner()
+        };
+
+        // Claims are only patched once the backing volume has grown everywhere
+        let volume = if wait || patch_pvc {
+            self.wait_for_volume_helper(&state, &volume.name, |volume| {
+                volume.size_gi >= size_gi && volume.is_ready()
+            })
+            .await?
+        } else {
+            volume
+        };
+
+        if patch_pvc {
+            let size = v0::VolumeSize::from_gi(size_gi);
+            let claims = k8s::get_volume_claims(None, &state, &volume).await?;
+            if claims.is_empty() {
+                self.verbosely(format_args!(
+                    "No persistent volume claim is bound to volume {}",
+                    volume.name
+                ))?;
+            }
+            for claim in claims {
+                k8s::resize_volume_claim(None, &claim, &size).await?;
+                self.inform(format_args!(
+                    "Persistent volume claim {} requests {}",
+                    claim, size
+                ))?;
+            }
+        }
+
+        Output::from(volume).print(&self.stdout, self.json)
+    }
+}
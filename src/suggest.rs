@@ -0,0 +1,97 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use itertools::Itertools;
+
+/// "did you mean" hint for the candidates closest to the word, if any is close enough
+pub(crate) fn did_you_mean<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    let matches = closest(word, candidates);
+    if matches.is_empty() {
+        None
+    } else {
+        let matches = matches
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .join(" or ");
+        Some(format!("did you mean {}?", matches))
+    }
+}
+
+/// Candidates at the smallest edit distance from the word,
+/// as long as it is small enough to be a likely typo
+fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let limit = (word.chars().count() / 3).max(1);
+    let mut best = limit;
+    let mut matches = vec![];
+    for candidate in candidates {
+        let distance = edit_distance(word, candidate);
+        if distance < best {
+            best = distance;
+            matches.clear();
+        }
+        if distance == best && !matches.contains(&candidate) {
+            matches.push(candidate);
+        }
+    }
+    matches
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Location;
+
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("us-east1", "us-east-1"), 1);
+    }
+
+    #[test]
+    fn suggestions() {
+        let names = ["alfa", "alpha", "bravo"];
+        assert_eq!(closest("alfa", names.iter().copied()), ["alfa"]);
+        assert_eq!(closest("alpa", names.iter().copied()), ["alfa", "alpha"]);
+        assert!(closest("charlie", names.iter().copied()).is_empty());
+        assert_eq!(
+            did_you_mean("brovo", names.iter().copied()).as_deref(),
+            Some("did you mean `bravo`?")
+        );
+    }
+
+    #[test]
+    fn region_suggestions() {
+        let err = "us-east1".parse::<Location>().unwrap_err();
+        assert!(err.ends_with("did you mean `us-east-1`?"), "{}", err);
+        let err = "west-europe".parse::<Location>().unwrap_err();
+        assert!(err.ends_with("did you mean `westeurope`?"), "{}", err);
+        let err = "azure/west-europe".parse::<Location>().unwrap_err();
+        assert!(err.ends_with("did you mean `azure/westeurope`?"), "{}", err);
+    }
+}
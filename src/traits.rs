@@ -3,10 +3,16 @@
 // Use is subject to license terms.
 //
 
-pub(crate) trait CloudRegion {
+pub(crate) trait CloudRegion: Sized + 'static {
     const VENDOR: &'static str;
     const VENDOR_PREFIX: &'static str;
+    /// Every region statehub can be deployed to
+    const ALL: &'static [Self];
     fn as_str(&self) -> &'static str;
+    fn display_name(&self) -> &'static str;
+    fn geography(&self) -> &'static str;
+    /// Approximate latitude and longitude of the region data centers
+    fn coordinates(&self) -> (f64, f64);
 }
 
 pub(crate) trait Show {
@@ -24,27 +24,36 @@ pub use cluster::{
     Cluster, ClusterLocationAws, ClusterLocationAzure, ClusterLocations, ClusterName, ClusterToken,
     CreateClusterDto, Provider,
 };
-pub use error::{Error, Permission, StatehubError};
+pub use error::{Error, Missing, Permission, StatehubError};
 pub use gcp::GcpRegion;
 pub use helm::Helm;
 pub use login::Login;
+pub use metadata::{
+    validate_label_key, Annotation, AnnotationChange, Label, LabelChange, Labels, Metadata,
+    UpdateMetadataDto,
+};
+pub use region::{Region, Vendor};
 pub use state::{
     Condition, CreateStateDto, CreateStateLocationAwsDto, CreateStateLocationAzureDto,
     CreateStateLocationsDto, ProvisioningStatus, State, StateLocationAws, StateLocationAzure,
-    StateLocationStatus, StateLocations, StateName,
+    StateLocationStatus, StateLocations, StateName, StorageClass,
 };
 pub use volume::{
-    CreateVolumeDto, LocationVolumeStatus, StateLocationVolumeProgress, Volume, VolumeBindingMode,
-    VolumeFileSystem, VolumeLocation, VolumeName, VolumeStatus,
+    CreateVolumeDto, LocationVolumeStatus, ResizeVolumeDto, StateLocationVolumeProgress, Volume,
+    VolumeBindingMode, VolumeFileSystem, VolumeLocation, VolumeName, VolumeSize, VolumeStatus,
 };
 
 mod aws;
 mod azure;
 mod cluster;
 mod error;
+#[cfg(test)]
+pub(crate) mod fixtures;
 mod gcp;
 mod helm;
 mod login;
+mod metadata;
+mod region;
 mod state;
 mod volume;
 
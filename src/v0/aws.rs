@@ -11,17 +11,22 @@ mod impls;
     Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
 )]
 pub enum AwsRegion {
+    AfSouth1,
+    ApEast1,
     ApNortheast1,
     ApNortheast2,
+    ApNortheast3,
     ApSouth1,
     ApSoutheast1,
     ApSoutheast2,
     CaCentral1,
     EuCentral1,
     EuNorth1,
+    EuSouth1,
     EuWest1,
     EuWest2,
     EuWest3,
+    MeSouth1,
     SaEast1,
     UsEast1,
     UsEast2,
@@ -11,19 +11,48 @@ use super::*;
 impl CloudRegion for AwsRegion {
     const VENDOR: &'static str = "AWS";
     const VENDOR_PREFIX: &'static str = "aws/";
+    const ALL: &'static [Self] = &[
+        Self::AfSouth1,
+        Self::ApEast1,
+        Self::ApNortheast1,
+        Self::ApNortheast2,
+        Self::ApNortheast3,
+        Self::ApSouth1,
+        Self::ApSoutheast1,
+        Self::ApSoutheast2,
+        Self::CaCentral1,
+        Self::EuCentral1,
+        Self::EuNorth1,
+        Self::EuSouth1,
+        Self::EuWest1,
+        Self::EuWest2,
+        Self::EuWest3,
+        Self::MeSouth1,
+        Self::SaEast1,
+        Self::UsEast1,
+        Self::UsEast2,
+        Self::UsWest1,
+        Self::UsWest2,
+    ];
+
     fn as_str(&self) -> &'static str {
         match self {
+            Self::AfSouth1 => "af-south-1",
+            Self::ApEast1 => "ap-east-1",
             Self::ApNortheast1 => "ap-northeast-1",
             Self::ApNortheast2 => "ap-northeast-2",
+            Self::ApNortheast3 => "ap-northeast-3",
             Self::ApSouth1 => "ap-south-1",
             Self::ApSoutheast1 => "ap-southeast-1",
             Self::ApSoutheast2 => "ap-southeast-2",
             Self::CaCentral1 => "ca-central-1",
             Self::EuCentral1 => "eu-central-1",
             Self::EuNorth1 => "eu-north-1",
+            Self::EuSouth1 => "eu-south-1",
             Self::EuWest1 => "eu-west-1",
             Self::EuWest2 => "eu-west-2",
             Self::EuWest3 => "eu-west-3",
+            Self::MeSouth1 => "me-south-1",
             Self::SaEast1 => "sa-east-1",
             Self::UsEast1 => "us-east-1",
             Self::UsEast2 => "us-east-2",
@@ -31,6 +60,84 @@ impl CloudRegion for AwsRegion {
             Self::UsWest2 => "us-west-2",
         }
     }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::AfSouth1 => "Africa (Cape Town)",
+            Self::ApEast1 => "Asia Pacific (Hong Kong)",
+            Self::ApNortheast1 => "Asia Pacific (Tokyo)",
+            Self::ApNortheast2 => "Asia Pacific (Seoul)",
+            Self::ApNortheast3 => "Asia Pacific (Osaka)",
+            Self::ApSouth1 => "Asia Pacific (Mumbai)",
+            Self::ApSoutheast1 => "Asia Pacific (Singapore)",
+            Self::ApSoutheast2 => "Asia Pacific (Sydney)",
+            Self::CaCentral1 => "Canada (Central)",
+            Self::EuCentral1 => "Europe (Frankfurt)",
+            Self::EuNorth1 => "Europe (Stockholm)",
+            Self::EuSouth1 => "Europe (Milan)",
+            Self::EuWest1 => "Europe (Ireland)",
+            Self::EuWest2 => "Europe (London)",
+            Self::EuWest3 => "Europe (Paris)",
+            Self::MeSouth1 => "Middle East (Bahrain)",
+            Self::SaEast1 => "South America (São Paulo)",
+            Self::UsEast1 => "US East (N. Virginia)",
+            Self::UsEast2 => "US East (Ohio)",
+            Self::UsWest1 => "US West (N. California)",
+            Self::UsWest2 => "US West (Oregon)",
+        }
+    }
+
+    fn geography(&self) -> &'static str {
+        match self {
+            Self::AfSouth1 => "Africa",
+            Self::ApEast1 => "Asia Pacific",
+            Self::ApNortheast1 => "Asia Pacific",
+            Self::ApNortheast2 => "Asia Pacific",
+            Self::ApNortheast3 => "Asia Pacific",
+            Self::ApSouth1 => "Asia Pacific",
+            Self::ApSoutheast1 => "Asia Pacific",
+            Self::ApSoutheast2 => "Asia Pacific",
+            Self::CaCentral1 => "North America",
+            Self::EuCentral1 => "Europe",
+            Self::EuNorth1 => "Europe",
+            Self::EuSouth1 => "Europe",
+            Self::EuWest1 => "Europe",
+            Self::EuWest2 => "Europe",
+            Self::EuWest3 => "Europe",
+            Self::MeSouth1 => "Middle East",
+            Self::SaEast1 => "South America",
+            Self::UsEast1 => "North America",
+            Self::UsEast2 => "North America",
+            Self::UsWest1 => "North America",
+            Self::UsWest2 => "North America",
+        }
+    }
+
+    fn coordinates(&self) -> (f64, f64) {
+        match self {
+            Self::AfSouth1 => (-33.92, 18.42),
+            Self::ApEast1 => (22.32, 114.17),
+            Self::ApNortheast1 => (35.68, 139.69),
+            Self::ApNortheast2 => (37.57, 126.98),
+            Self::ApNortheast3 => (34.69, 135.5),
+            Self::ApSouth1 => (19.08, 72.88),
+            Self::ApSoutheast1 => (1.35, 103.82),
+            Self::ApSoutheast2 => (-33.87, 151.21),
+            Self::CaCentral1 => (45.5, -73.57),
+            Self::EuCentral1 => (50.11, 8.68),
+            Self::EuNorth1 => (59.33, 18.07),
+            Self::EuSouth1 => (45.46, 9.19),
+            Self::EuWest1 => (53.35, -6.26),
+            Self::EuWest2 => (51.51, -0.13),
+            Self::EuWest3 => (48.86, 2.35),
+            Self::MeSouth1 => (26.07, 50.56),
+            Self::SaEast1 => (-23.55, -46.63),
+            Self::UsEast1 => (39.04, -77.49),
+            Self::UsEast2 => (39.96, -83.0),
+            Self::UsWest1 => (37.35, -121.96),
+            Self::UsWest2 => (45.84, -119.7),
+        }
+    }
 }
 
 impl str::FromStr for AwsRegion {
@@ -39,17 +146,22 @@ impl str::FromStr for AwsRegion {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let text = s.strip_prefix(Self::VENDOR_PREFIX).unwrap_or(s);
         match text {
+            "af-south-1" => Ok(Self::AfSouth1),
+            "ap-east-1" => Ok(Self::ApEast1),
             "ap-northeast-1" => Ok(Self::ApNortheast1),
             "ap-northeast-2" => Ok(Self::ApNortheast2),
+            "ap-northeast-3" => Ok(Self::ApNortheast3),
             "ap-south-1" => Ok(Self::ApSouth1),
             "ap-southeast-1" => Ok(Self::ApSoutheast1),
             "ap-southeast-2" => Ok(Self::ApSoutheast2),
             "ca-central-1" => Ok(Self::CaCentral1),
             "eu-central-1" => Ok(Self::EuCentral1),
             "eu-north-1" => Ok(Self::EuNorth1),
+            "eu-south-1" => Ok(Self::EuSouth1),
             "eu-west-1" => Ok(Self::EuWest1),
             "eu-west-2" => Ok(Self::EuWest2),
             "eu-west-3" => Ok(Self::EuWest3),
+            "me-south-1" => Ok(Self::MeSouth1),
             "sa-east-1" => Ok(Self::SaEast1),
             "us-east-1" => Ok(Self::UsEast1),
             "us-east-2" => Ok(Self::UsEast2),
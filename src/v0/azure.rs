@@ -11,16 +11,34 @@ mod impls;
     Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
 )]
 pub enum AzureRegion {
+    AustraliaEast,
+    BrazilSouth,
+    CanadaCentral,
+    CentralIndia,
     CentralUs,
+    EastAsia,
     EastUs,
     EastUs2,
     FranceCentral,
+    GermanyWestCentral,
     JapanEast,
+    JapanWest,
+    KoreaCentral,
+    NorthCentralUs,
     NorthEurope,
+    NorwayEast,
+    SouthAfricaNorth,
+    SouthCentralUs,
     SouthEastasia,
+    SwitzerlandNorth,
+    UaeNorth,
     UkSouth,
+    UkWest,
+    WestCentralUs,
     WestEurope,
+    WestUs,
     WestUs2,
+    WestUs3,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -11,19 +11,166 @@ use super::*;
 impl CloudRegion for AzureRegion {
     const VENDOR: &'static str = "Azure";
     const VENDOR_PREFIX: &'static str = "azure/";
+    const ALL: &'static [Self] = &[
+        Self::AustraliaEast,
+        Self::BrazilSouth,
+        Self::CanadaCentral,
+        Self::CentralIndia,
+        Self::CentralUs,
+        Self::EastAsia,
+        Self::EastUs,
+        Self::EastUs2,
+        Self::FranceCentral,
+        Self::GermanyWestCentral,
+        Self::JapanEast,
+        Self::JapanWest,
+        Self::KoreaCentral,
+        Self::NorthCentralUs,
+        Self::NorthEurope,
+        Self::NorwayEast,
+        Self::SouthAfricaNorth,
+        Self::SouthCentralUs,
+        Self::SouthEastasia,
+        Self::SwitzerlandNorth,
+        Self::UaeNorth,
+        Self::UkSouth,
+        Self::UkWest,
+        Self::WestCentralUs,
+        Self::WestEurope,
+        Self::WestUs,
+        Self::WestUs2,
+        Self::WestUs3,
+    ];
 
     fn as_str(&self) -> &'static str {
         match self {
+            Self::AustraliaEast => "australiaeast",
+            Self::BrazilSouth => "brazilsouth",
+            Self::CanadaCentral => "canadacentral",
+            Self::CentralIndia => "centralindia",
             Self::CentralUs => "centralus",
+            Self::EastAsia => "eastasia",
             Self::EastUs => "eastus",
             Self::EastUs2 => "eastus2",
             Self::FranceCentral => "francecentral",
+            Self::GermanyWestCentral => "germanywestcentral",
             Self::JapanEast => "japaneast",
+            Self::JapanWest => "japanwest",
+            Self::KoreaCentral => "koreacentral",
+            Self::NorthCentralUs => "northcentralus",
             Self::NorthEurope => "northeurope",
+            Self::NorwayEast => "norwayeast",
+            Self::SouthAfricaNorth => "southafricanorth",
+            Self::SouthCentralUs => "southcentralus",
             Self::SouthEastasia => "southeastasia",
+            Self::SwitzerlandNorth => "switzerlandnorth",
+            Self::UaeNorth => "uaenorth",
             Self::UkSouth => "uksouth",
+            Self::UkWest => "ukwest",
+            Self::WestCentralUs => "westcentralus",
             Self::WestEurope => "westeurope",
+            Self::WestUs => "westus",
             Self::WestUs2 => "westus2",
+            Self::WestUs3 => "westus3",
+        }
+    }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::AustraliaEast => "Australia East (New South Wales)",
+            Self::BrazilSouth => "Brazil South (Sao Paulo State)",
+            Self::CanadaCentral => "Canada Central (Toronto)",
+            Self::CentralIndia => "Central India (Pune)",
+            Self::CentralUs => "Central US (Iowa)",
+            Self::EastAsia => "East Asia (Hong Kong)",
+            Self::EastUs => "East US (Virginia)",
+            Self::EastUs2 => "East US 2 (Virginia)",
+            Self::FranceCentral => "France Central (Paris)",
+            Self::GermanyWestCentral => "Germany West Central (Frankfurt)",
+            Self::JapanEast => "Japan East (Tokyo)",
+            Self::JapanWest => "Japan West (Osaka)",
+            Self::KoreaCentral => "Korea Central (Seoul)",
+            Self::NorthCentralUs => "North Central US (Illinois)",
+            Self::NorthEurope => "North Europe (Ireland)",
+            Self::NorwayEast => "Norway East (Oslo)",
+            Self::SouthAfricaNorth => "South Africa North (Johannesburg)",
+            Self::SouthCentralUs => "South Central US (Texas)",
+            Self::SouthEastasia => "Southeast Asia (Singapore)",
+            Self::SwitzerlandNorth => "Switzerland North (Zurich)",
+            Self::UaeNorth => "UAE North (Dubai)",
+            Self::UkSouth => "UK South (London)",
+            Self::UkWest => "UK West (Cardiff)",
+            Self::WestCentralUs => "West Central US (Wyoming)",
+            Self::WestEurope => "West Europe (Netherlands)",
+            Self::WestUs => "West US (California)",
+            Self::WestUs2 => "West US 2 (Washington)",
+            Self::WestUs3 => "West US 3 (Phoenix)",
+        }
+    }
+
+    fn geography(&self) -> &'static str {
+        match self {
+            Self::AustraliaEast => "Asia Pacific",
+            Self::BrazilSouth => "South America",
+            Self::CanadaCentral => "North America",
+            Self::CentralIndia => "Asia Pacific",
+            Self::CentralUs => "North America",
+            Self::EastAsia => "Asia Pacific",
+            Self::EastUs => "North America",
+            Self::EastUs2 => "North America",
+            Self::FranceCentral => "Europe",
+            Self::GermanyWestCentral => "Europe",
+            Self::JapanEast => "Asia Pacific",
+            Self::JapanWest => "Asia Pacific",
+            Self::KoreaCentral => "Asia Pacific",
+            Self::NorthCentralUs => "North America",
+            Self::NorthEurope => "Europe",
+            Self::NorwayEast => "Europe",
+            Self::SouthAfricaNorth => "Africa",
+            Self::SouthCentralUs => "North America",
+            Self::SouthEastasia => "Asia Pacific",
+            Self::SwitzerlandNorth => "Europe",
+            Self::UaeNorth => "Middle East",
+            Self::UkSouth => "Europe",
+            Self::UkWest => "Europe",
+            Self::WestCentralUs => "North America",
+            Self::WestEurope => "Europe",
+            Self::WestUs => "North America",
+            Self::WestUs2 => "North America",
+            Self::WestUs3 => "North America",
+        }
+    }
+
+    fn coordinates(&self) -> (f64, f64) {
+        match self {
+            Self::AustraliaEast => (-33.86, 151.21),
+            Self::BrazilSouth => (-23.55, -46.63),
+            Self::CanadaCentral => (43.65, -79.38),
+            Self::CentralIndia => (18.58, 73.92),
+            Self::CentralUs => (41.59, -93.62),
+            Self::EastAsia => (22.27, 114.19),
+            Self::EastUs => (37.37, -79.82),
+            Self::EastUs2 => (36.68, -78.39),
+            Self::FranceCentral => (46.38, 2.37),
+            Self::GermanyWestCentral => (50.11, 8.68),
+            Self::JapanEast => (35.68, 139.77),
+            Self::JapanWest => (34.69, 135.5),
+            Self::KoreaCentral => (37.57, 126.98),
+            Self::NorthCentralUs => (41.88, -87.63),
+            Self::NorthEurope => (53.35, -6.26),
+            Self::NorwayEast => (59.91, 10.75),
+            Self::SouthAfricaNorth => (-26.2, 28.05),
+            Self::SouthCentralUs => (29.42, -98.49),
+            Self::SouthEastasia => (1.28, 103.83),
+            Self::SwitzerlandNorth => (47.45, 8.56),
+            Self::UaeNorth => (25.27, 55.31),
+            Self::UkSouth => (50.94, -0.8),
+            Self::UkWest => (51.48, -3.18),
+            Self::WestCentralUs => (40.89, -110.23),
+            Self::WestEurope => (52.37, 4.9),
+            Self::WestUs => (37.78, -122.42),
+            Self::WestUs2 => (47.23, -119.85),
+            Self::WestUs3 => (33.45, -112.07),
         }
     }
 }
@@ -34,16 +181,34 @@ impl str::FromStr for AzureRegion {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let text = s.strip_prefix(Self::VENDOR_PREFIX).unwrap_or(s);
         match text {
+            "australiaeast" => Ok(Self::AustraliaEast),
+            "brazilsouth" => Ok(Self::BrazilSouth),
+            "canadacentral" => Ok(Self::CanadaCentral),
+            "centralindia" => Ok(Self::CentralIndia),
             "centralus" => Ok(Self::CentralUs),
+            "eastasia" => Ok(Self::EastAsia),
             "eastus" => Ok(Self::EastUs),
             "eastus2" => Ok(Self::EastUs2),
             "francecentral" => Ok(Self::FranceCentral),
+            "germanywestcentral" => Ok(Self::GermanyWestCentral),
             "japaneast" => Ok(Self::JapanEast),
+            "japanwest" => Ok(Self::JapanWest),
+            "koreacentral" => Ok(Self::KoreaCentral),
+            "northcentralus" => Ok(Self::NorthCentralUs),
             "northeurope" => Ok(Self::NorthEurope),
+            "norwayeast" => Ok(Self::NorwayEast),
+            "southafricanorth" => Ok(Self::SouthAfricaNorth),
+            "southcentralus" => Ok(Self::SouthCentralUs),
             "southeastasia" => Ok(Self::SouthEastasia),
+            "switzerlandnorth" => Ok(Self::SwitzerlandNorth),
+            "uaenorth" => Ok(Self::UaeNorth),
             "uksouth" => Ok(Self::UkSouth),
+            "ukwest" => Ok(Self::UkWest),
+            "westcentralus" => Ok(Self::WestCentralUs),
             "westeurope" => Ok(Self::WestEurope),
+            "westus" => Ok(Self::WestUs),
             "westus2" => Ok(Self::WestUs2),
+            "westus3" => Ok(Self::WestUs3),
             other => Err(InvalidRegion::new(Self::VENDOR, other)),
         }
     }
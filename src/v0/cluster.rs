@@ -16,6 +16,8 @@ pub struct Cluster {
     #[serde(default)]
     pub locations: ClusterLocations,
     pub helm: Vec<Helm>,
+    #[serde(flatten)]
+    pub metadata: Metadata,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, PartialOrd, Hash)]
@@ -32,6 +34,8 @@ pub struct CreateClusterDto {
     pub name: ClusterName,
     pub provider: Provider,
     pub locations: ClusterLocations,
+    #[serde(flatten)]
+    pub metadata: Metadata,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -13,7 +13,7 @@ use crate::k8s;
 use super::*;
 
 impl Cluster {
-    const CLUSTER: Emoji<'static, 'static> = Emoji("☸", "*");
+    pub(crate) const CLUSTER: Emoji<'static, 'static> = Emoji("☸", "*");
 
     pub(crate) fn all_locations(&self) -> Vec<Location> {
         let aws = self.locations.aws.iter().map(|aws| aws.region.into());
@@ -118,10 +118,12 @@ impl Show for Cluster {
         let helm = k8s::Helm::new("statehub-system".to_string(), None, true);
         let helm = helm.command(self);
         format!(
-            "{}\n{}\n{}\n{}\n{}\n{}",
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
             format_args!("Cluster:     {}", self.name),
             format_args!("Id:          {}", self.id),
             format_args!("Locations:   {}", self.locations.show()),
+            format_args!("Labels:      {}", self.metadata.labels.show()),
+            format_args!("Annotations: {}", self.metadata.annotations.show()),
             format_args!("Created:     {}", HumanTime::from(self.created)),
             format_args!("Modified:    {}", HumanTime::from(self.modified)),
             format_args!("Helm install:\n{}", helm.detailed_show())
@@ -179,7 +181,7 @@ impl str::FromStr for Provider {
             "eks" => Ok(Self::Eks),
             "aks" => Ok(Self::Aks),
             "kops" => Ok(Self::Kops),
-            "genetic" => Ok(Self::Generic),
+            "generic" => Ok(Self::Generic),
             other => anyhow::bail!("Invalid K8s provider: {}", other),
         }
     }
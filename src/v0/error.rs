@@ -64,6 +64,14 @@ pub enum StatehubError {
     },
 }
 
+/// Object reported as missing by a not found error
+#[derive(Clone, Debug, PartialEq)]
+pub enum Missing {
+    State(StateName),
+    Cluster(ClusterName),
+    Volume(StateName, VolumeName),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, SerializeDisplay, DeserializeFromStr)]
 pub enum Permission {
     ReadClusters,
@@ -8,6 +8,8 @@ use std::borrow::Cow;
 use bytes::Bytes;
 use serde_json as json;
 
+use crate::suggest;
+
 use super::*;
 
 impl Error {
@@ -34,9 +36,72 @@ impl Error {
         }
     }
 
+    /// Client side counterpart of the API error, used when an ownership change
+    /// would otherwise silently take the state away from its current owner
+    pub fn cluster_is_state_owner(cluster: &ClusterName, state: &StateName) -> Self {
+        let status = reqwest::StatusCode::CONFLICT;
+        let http_code = status.as_u16();
+        let http_status = status.canonical_reason().unwrap_or_default().to_string();
+        let msg = format!("State {} is owned by cluster {}", state, cluster);
+        let error = StatehubError::ClusterIsStateOwner {
+            cluster: cluster.clone(),
+            state: state.clone(),
+        };
+        Self {
+            http_code,
+            http_status,
+            error,
+            msg,
+        }
+    }
+
+    pub fn is_cluster_is_state_owner(&self) -> bool {
+        matches!(self.error, StatehubError::ClusterIsStateOwner { .. })
+    }
+
+    pub fn is_state_not_found(&self) -> bool {
+        matches!(self.error, StatehubError::StateNotFound { .. })
+    }
+
     pub fn is_volume_not_found(&self) -> bool {
         matches!(self.error, StatehubError::VolumeNotFound { .. })
     }
+
+    /// Object the error reports as missing, if it is a not found error
+    pub fn missing(&self) -> Option<Missing> {
+        match &self.error {
+            StatehubError::StateNotFound { state } => Some(Missing::State(state.clone())),
+            StatehubError::ClusterNotFound { cluster } => Some(Missing::Cluster(cluster.clone())),
+            StatehubError::VolumeNotFound { state, volume } => {
+                Some(Missing::Volume(state.clone(), volume.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// "did you mean" hint picked out of the existing names of the missing object kind
+    pub fn suggestion<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Option<String> {
+        let missing = match &self.error {
+            StatehubError::StateNotFound { state } => state.as_ref(),
+            StatehubError::ClusterNotFound { cluster } => cluster.as_ref(),
+            StatehubError::VolumeNotFound { volume, .. } => volume.as_ref(),
+            _ => return None,
+        };
+        suggest::did_you_mean(missing, names)
+    }
+
+    /// Add the "did you mean" hint to the error message
+    pub fn with_suggestions<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        if let Some(hint) = self.suggestion(names) {
+            self.msg = format!("{}, {}", self.msg, hint);
+        }
+        self
+    }
+
+    pub fn is_unauthorized(&self) -> bool {
+        self.http_code == reqwest::StatusCode::UNAUTHORIZED.as_u16()
+            || matches!(self.error, StatehubError::InvalidToken)
+    }
 }
 
 impl Permission {
@@ -194,6 +259,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cluster_is_state_owner_local() {
+        let err = Error::cluster_is_state_owner(&"zulu".into(), &"time".into());
+        assert!(err.is_cluster_is_state_owner());
+        assert_eq!(err.http_code, 409);
+        assert_eq!(err.to_string(), "State time is owned by cluster zulu");
+    }
+
     #[test]
     fn state_name_conflict() {
         let text = r#"{"httpCode":409,"httpStatus":"Conflict","error":{"errorCode":"StateNameConflict","state":"time"},"msg":"string"}"#;
@@ -215,4 +288,24 @@ mod tests {
             assert_eq!(volume, "333");
         }
     }
+
+    #[test]
+    fn state_not_found() {
+        let text = r#"{"httpCode":404,"httpStatus":"Not Found","error":{"errorCode":"StateNotFound","state":"ttt"},"msg":"State ttt does not exist"}"#;
+        let err: Error = json::from_str(text).unwrap();
+        assert!(err.is_state_not_found());
+        assert!(!err.is_volume_not_found());
+    }
+
+    #[test]
+    fn not_found_suggestions() {
+        let text = r#"{"httpCode":404,"httpStatus":"Not Found","error":{"errorCode":"ClusterNotFound","cluster":"prod-eu"},"msg":"Cluster prod-eu does not exist"}"#;
+        let err: Error = json::from_str(text).unwrap();
+        assert_eq!(err.missing(), Some(Missing::Cluster("prod-eu".into())));
+        let err = err.with_suggestions(vec!["prod-eu1", "prod-eu2", "staging-eu"]);
+        assert_eq!(
+            err.to_string(),
+            "Cluster prod-eu does not exist, did you mean `prod-eu1` or `prod-eu2`?"
+        );
+    }
 }
@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+//! API objects for unit tests, locations are given as `vendor/region` strings
+
+use serde_json::{json, Value};
+
+use super::*;
+
+/// State with `(location, status)` pairs, e.g. `("aws/us-east-1", "ok")`
+pub(crate) fn state(name: &str, locations: &[(&str, &str)]) -> State {
+    let location = |region: &str, status: &str| {
+        json!({
+            "region": region,
+            "status": status,
+            "volumes": [],
+            "privateLinkService": null
+        })
+    };
+    let mut state = State::new(name);
+    state.locations = serde_json::from_value(json!({
+        "aws": by_vendor("aws", locations, location),
+        "azure": by_vendor("azure", locations, location),
+    }))
+    .unwrap();
+    state
+}
+
+/// Cluster in the given locations
+pub(crate) fn cluster(name: &str, locations: &[&str]) -> Cluster {
+    let locations = locations
+        .iter()
+        .map(|location| (*location, ()))
+        .collect::<Vec<_>>();
+    let aws = by_vendor(
+        "aws",
+        &locations,
+        |region, _| json!({ "region": region, "accountPrincipal": null }),
+    );
+    let azure = by_vendor("azure", &locations, |region, _| json!({ "region": region }));
+    serde_json::from_value(json!({
+        "id": Uuid::nil(),
+        "name": name,
+        "locations": { "aws": aws, "azure": azure },
+        "helm": [],
+        "created": "2021-06-01T00:00:00Z",
+        "modified": "2021-06-01T00:00:00Z"
+    }))
+    .unwrap()
+}
+
+/// Volume with `(location, status, percent synchronized)` replicas
+pub(crate) fn volume(
+    name: &str,
+    active: Option<&str>,
+    locations: &[(&str, &str, Option<u64>)],
+) -> Volume {
+    let locations = locations
+        .iter()
+        .map(|(location, status, synchronized)| {
+            json!({
+                "name": location,
+                "status": { "value": status },
+                "progress": synchronized.map(|synchronized| json!({
+                    "bytesSynchronized": synchronized,
+                    "bytesTotal": 100
+                }))
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::from_value(json!({
+        "id": Uuid::nil(),
+        "name": name,
+        "sizeGi": 10,
+        "fsType": "ext4",
+        "activeLocation": active,
+        "locations": locations,
+        "format": null,
+        "created": "2021-06-01T00:00:00Z",
+        "modified": "2021-06-01T00:00:00Z"
+    }))
+    .unwrap()
+}
+
+fn by_vendor<T: Copy>(
+    vendor: &str,
+    locations: &[(&str, T)],
+    object: impl Fn(&str, T) -> Value,
+) -> Vec<Value> {
+    locations
+        .iter()
+        .filter_map(|(location, value)| {
+            let region = location.strip_prefix(vendor)?.strip_prefix('/')?;
+            Some(object(region, *value))
+        })
+        .collect()
+}
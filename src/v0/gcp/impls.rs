@@ -8,12 +8,32 @@ use super::*;
 impl CloudRegion for GcpRegion {
     const VENDOR: &'static str = "GCP";
     const VENDOR_PREFIX: &'static str = "gcp/";
+    // No GCP region is supported yet
+    const ALL: &'static [Self] = &[];
 
     fn as_str(&self) -> &'static str {
         match self {
             Self::Antarctica => "antarctica",
         }
     }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::Antarctica => "Antarctica",
+        }
+    }
+
+    fn geography(&self) -> &'static str {
+        match self {
+            Self::Antarctica => "Antarctica",
+        }
+    }
+
+    fn coordinates(&self) -> (f64, f64) {
+        match self {
+            Self::Antarctica => (-90.0, 0.0),
+        }
+    }
 }
 
 impl str::FromStr for GcpRegion {
@@ -0,0 +1,59 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use std::collections::BTreeMap;
+
+use super::*;
+
+mod impls;
+
+pub use impls::validate_label_key;
+
+pub type Labels = BTreeMap<String, String>;
+
+/// User defined labels (used to select objects) and annotations (free form notes)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Labels::is_empty")]
+    pub labels: Labels,
+    #[serde(default, skip_serializing_if = "Labels::is_empty")]
+    pub annotations: Labels,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateMetadataDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Labels>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Labels>,
+}
+
+/// Single `key=value` label
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub key: String,
+    pub value: String,
+}
+
+/// `key=value` sets the label, `key-` removes it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LabelChange {
+    Set(Label),
+    Remove(String),
+}
+
+/// Single `key=value` annotation, the value is free form text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    pub key: String,
+    pub value: String,
+}
+
+/// `key=value` sets the annotation, `key-` removes it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnnotationChange {
+    Set(Annotation),
+    Remove(String),
+}
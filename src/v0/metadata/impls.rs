@@ -0,0 +1,306 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use thiserror::Error;
+
+use super::*;
+
+const MAX_NAME_LEN: usize = 63;
+const MAX_PREFIX_LEN: usize = 253;
+
+#[derive(Debug, Error)]
+#[error(r#"Invalid label "{label}": {reason}"#)]
+pub struct InvalidLabel {
+    label: String,
+    reason: &'static str,
+}
+
+impl InvalidLabel {
+    pub(crate) fn new(label: &str, reason: &'static str) -> Self {
+        let label = label.to_string();
+        Self { label, reason }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error(r#"Invalid annotation "{annotation}": {reason}"#)]
+pub struct InvalidAnnotation {
+    annotation: String,
+    reason: &'static str,
+}
+
+impl InvalidAnnotation {
+    fn new(annotation: &str, reason: &'static str) -> Self {
+        let annotation = annotation.to_string();
+        Self { annotation, reason }
+    }
+}
+
+impl Metadata {
+    pub fn new(
+        labels: impl IntoIterator<Item = Label>,
+        annotations: impl IntoIterator<Item = Annotation>,
+    ) -> Self {
+        let labels = labels.into_iter().map(Label::into_pair).collect();
+        let annotations = annotations.into_iter().map(Annotation::into_pair).collect();
+        Self {
+            labels,
+            annotations,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.annotations.is_empty()
+    }
+}
+
+impl Label {
+    fn into_pair(self) -> (String, String) {
+        (self.key, self.value)
+    }
+}
+
+impl Annotation {
+    fn into_pair(self) -> (String, String) {
+        (self.key, self.value)
+    }
+}
+
+impl LabelChange {
+    pub fn apply(&self, labels: &mut Labels) {
+        match self {
+            Self::Set(label) => {
+                labels.insert(label.key.clone(), label.value.clone());
+            }
+            Self::Remove(key) => {
+                labels.remove(key);
+            }
+        }
+    }
+}
+
+impl str::FromStr for Label {
+    type Err = InvalidLabel;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| InvalidLabel::new(s, "expected key=value"))?;
+        validate_label_key(key).map_err(|reason| InvalidLabel::new(s, reason))?;
+        validate_value(value).map_err(|reason| InvalidLabel::new(s, reason))?;
+        let key = key.to_string();
+        let value = value.to_string();
+        Ok(Self { key, value })
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+impl str::FromStr for LabelChange {
+    type Err = InvalidLabel;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(key) = s.strip_suffix('-').filter(|_| !s.contains('=')) {
+            validate_label_key(key).map_err(|reason| InvalidLabel::new(s, reason))?;
+            Ok(Self::Remove(key.to_string()))
+        } else {
+            s.parse().map(Self::Set)
+        }
+    }
+}
+
+impl AnnotationChange {
+    pub fn apply(&self, annotations: &mut Labels) {
+        match self {
+            Self::Set(annotation) => {
+                annotations.insert(annotation.key.clone(), annotation.value.clone());
+            }
+            Self::Remove(key) => {
+                annotations.remove(key);
+            }
+        }
+    }
+}
+
+/// Only the key follows the label rules, the value may be any text
+impl str::FromStr for Annotation {
+    type Err = InvalidAnnotation;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| InvalidAnnotation::new(s, "expected key=value"))?;
+        validate_label_key(key).map_err(|reason| InvalidAnnotation::new(s, reason))?;
+        let key = key.to_string();
+        let value = value.to_string();
+        Ok(Self { key, value })
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+impl str::FromStr for AnnotationChange {
+    type Err = InvalidAnnotation;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(key) = s.strip_suffix('-').filter(|_| !s.contains('=')) {
+            validate_label_key(key).map_err(|reason| InvalidAnnotation::new(s, reason))?;
+            Ok(Self::Remove(key.to_string()))
+        } else {
+            s.parse().map(Self::Set)
+        }
+    }
+}
+
+impl Show for Labels {
+    fn show(&self) -> String {
+        if self.is_empty() {
+            String::from("None")
+        } else {
+            self.iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .join(", ")
+        }
+    }
+}
+
+/// Same rules as Kubernetes labels: optional DNS subdomain prefix and a name
+pub fn validate_label_key(key: &str) -> Result<(), &'static str> {
+    let name = match key.rsplit_once('/') {
+        Some((prefix, name)) => {
+            if prefix.is_empty() || prefix.len() > MAX_PREFIX_LEN {
+                return Err("key prefix must be 1-253 characters");
+            }
+            if !prefix.split('.').all(|part| {
+                is_name(part, |c| {
+                    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'
+                })
+            }) {
+                return Err("key prefix must be a DNS subdomain");
+            }
+            name
+        }
+        None => key,
+    };
+    if name.is_empty() {
+        return Err("key must not be empty");
+    }
+    validate_name(name)
+}
+
+fn validate_value(value: &str) -> Result<(), &'static str> {
+    if value.is_empty() {
+        Ok(())
+    } else {
+        validate_name(value)
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), &'static str> {
+    if name.len() > MAX_NAME_LEN {
+        Err("must be no more than 63 characters")
+    } else if is_name(name, |c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+        Ok(())
+    } else {
+        Err("must consist of alphanumerics, '-', '_' or '.', starting and ending with an alphanumeric")
+    }
+}
+
+fn is_name(name: &str, allowed: impl Fn(char) -> bool) -> bool {
+    let alphanumeric = |c: Option<char>| matches!(c, Some(c) if c.is_ascii_alphanumeric());
+    alphanumeric(name.chars().next())
+        && alphanumeric(name.chars().last())
+        && name.chars().all(allowed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label() {
+        let label = "team=storage".parse::<Label>().unwrap();
+        assert_eq!(label.key, "team");
+        assert_eq!(label.value, "storage");
+        assert!("statehub.io/cost-center=r-and-d".parse::<Label>().is_ok());
+        assert!("empty=".parse::<Label>().is_ok());
+        for invalid in &[
+            "team",
+            "=storage",
+            "team=-storage",
+            "a b=c",
+            "/team=x",
+            "Team.IO/x=y",
+        ] {
+            assert!(invalid.parse::<Label>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn label_change() {
+        let mut labels = Labels::new();
+        "env=prod"
+            .parse::<LabelChange>()
+            .unwrap()
+            .apply(&mut labels);
+        "team=storage"
+            .parse::<LabelChange>()
+            .unwrap()
+            .apply(&mut labels);
+        assert_eq!(labels.show(), "env=prod, team=storage");
+        "env-".parse::<LabelChange>().unwrap().apply(&mut labels);
+        assert_eq!(labels.show(), "team=storage");
+        assert!("team=dev-".parse::<LabelChange>().is_err());
+        assert!("team/-".parse::<LabelChange>().is_err());
+    }
+
+    #[test]
+    fn annotation() {
+        let url = "docs=https://statehub.io/docs?topic=volumes#resize"
+            .parse::<Annotation>()
+            .unwrap();
+        assert_eq!(url.key, "docs");
+        assert_eq!(url.value, "https://statehub.io/docs?topic=volumes#resize");
+
+        let note = "statehub.io/note=Owned by the storage team, ask in #storage before resizing any volume of this state";
+        let note = note.parse::<Annotation>().unwrap();
+        assert!(note.value.len() > MAX_NAME_LEN);
+
+        assert!("contact=ops@example.com".parse::<Annotation>().is_ok());
+        assert!(r#"config={"replicas": 2}"#.parse::<Annotation>().is_ok());
+        assert!("note".parse::<Annotation>().is_err());
+        assert!("a b=c".parse::<Annotation>().is_err());
+    }
+
+    #[test]
+    fn annotation_change() {
+        let mut annotations = Labels::new();
+        "docs=https://statehub.io/docs"
+            .parse::<AnnotationChange>()
+            .unwrap()
+            .apply(&mut annotations);
+        assert_eq!(annotations.show(), "docs=https://statehub.io/docs");
+        "docs-"
+            .parse::<AnnotationChange>()
+            .unwrap()
+            .apply(&mut annotations);
+        assert!(annotations.is_empty());
+        assert_eq!(
+            "note=keep-".parse::<AnnotationChange>().unwrap(),
+            AnnotationChange::Set(Annotation {
+                key: String::from("note"),
+                value: String::from("keep-"),
+            })
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use super::*;
+
+mod impls;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Vendor {
+    Aws,
+    Azure,
+    Gcp,
+}
+
+/// Region catalog entry, as reported by the service or built in
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub vendor: Vendor,
+    pub name: String,
+    pub display_name: String,
+    pub geography: String,
+}
@@ -0,0 +1,201 @@
+//
+// Copyright (c) 2021 RepliXio Ltd. All rights reserved.
+// Use is subject to license terms.
+//
+
+use super::*;
+
+impl Vendor {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Aws => "aws",
+            Self::Azure => "azure",
+            Self::Gcp => "gcp",
+        }
+    }
+}
+
+impl fmt::Display for Vendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl str::FromStr for Vendor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "aws" => Ok(Self::Aws),
+            "azure" => Ok(Self::Azure),
+            "gcp" => Ok(Self::Gcp),
+            other => anyhow::bail!(
+                "Invalid cloud vendor: {}, expected aws, azure or gcp",
+                other
+            ),
+        }
+    }
+}
+
+impl Region {
+    /// Regions compiled into this release, used when the service does not provide the catalog
+    pub fn builtin() -> Vec<Self> {
+        let aws = AwsRegion::ALL
+            .iter()
+            .map(|region| Self::new(Vendor::Aws, region));
+        let azure = AzureRegion::ALL
+            .iter()
+            .map(|region| Self::new(Vendor::Azure, region));
+        let gcp = GcpRegion::ALL
+            .iter()
+            .map(|region| Self::new(Vendor::Gcp, region));
+        aws.chain(azure).chain(gcp).collect()
+    }
+
+    /// Regions listed by the service that this release does not know cannot be used as locations
+    pub fn is_usable(&self) -> bool {
+        format!("{}/{}", self.vendor, self.name)
+            .parse::<Location>()
+            .is_ok()
+    }
+
+    fn new(vendor: Vendor, region: &impl CloudRegion) -> Self {
+        Self {
+            vendor,
+            name: region.as_str().to_string(),
+            display_name: region.display_name().to_string(),
+            geography: region.geography().to_string(),
+        }
+    }
+}
+
+impl Show for Region {
+    fn show(&self) -> String {
+        let usable = if self.is_usable() {
+            ""
+        } else {
+            " (not usable with this release)"
+        };
+        format!(
+            "{:<24} {:<28} {}{}",
+            format!("{}/{}", self.vendor, self.name),
+            self.display_name,
+            self.geography,
+            usable
+        )
+    }
+}
+
+impl Show for Vec<Region> {
+    fn show(&self) -> String {
+        let regions = self.iter().map(Show::show).join("\n");
+        if self.iter().all(Region::is_usable) {
+            regions
+        } else {
+            format!(
+                "{}\n\nOnly regions built into this release of statehub CLI can be used as locations, upgrade it to use the others",
+                regions
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin() {
+        let regions = Region::builtin();
+        assert_eq!(
+            regions.len(),
+            AwsRegion::ALL.len() + AzureRegion::ALL.len() + GcpRegion::ALL.len()
+        );
+        for region in regions {
+            assert!(region.is_usable(), "{}/{}", region.vendor, region.name);
+        }
+    }
+
+    #[test]
+    fn unknown_region() {
+        let region = Region {
+            vendor: Vendor::Aws,
+            name: String::from("ap-southeast-3"),
+            display_name: String::from("Asia Pacific (Jakarta)"),
+            geography: String::from("Asia Pacific"),
+        };
+        assert!(!region.is_usable());
+        assert!(vec![region].show().contains("upgrade"));
+    }
+
+    /// Variants are listed in an exhaustive match, so a new one does not compile
+    /// until it is listed here, and then the test fails until it is in `ALL` too
+    macro_rules! variants {
+        ($region:ident: $($variant:ident),+ $(,)?) => {{
+            let _exhaustive = |region: $region| match region {
+                $($region::$variant)|+ => (),
+            };
+            [$($region::$variant),+]
+        }};
+    }
+
+    #[test]
+    fn all_regions() {
+        let aws = variants!(AwsRegion:
+            AfSouth1,
+            ApEast1,
+            ApNortheast1,
+            ApNortheast2,
+            ApNortheast3,
+            ApSouth1,
+            ApSoutheast1,
+            ApSoutheast2,
+            CaCentral1,
+            EuCentral1,
+            EuNorth1,
+            EuSouth1,
+            EuWest1,
+            EuWest2,
+            EuWest3,
+            MeSouth1,
+            SaEast1,
+            UsEast1,
+            UsEast2,
+            UsWest1,
+            UsWest2,
+        );
+        assert_eq!(AwsRegion::ALL, aws);
+
+        let azure = variants!(AzureRegion:
+            AustraliaEast,
+            BrazilSouth,
+            CanadaCentral,
+            CentralIndia,
+            CentralUs,
+            EastAsia,
+            EastUs,
+            EastUs2,
+            FranceCentral,
+            GermanyWestCentral,
+            JapanEast,
+            JapanWest,
+            KoreaCentral,
+            NorthCentralUs,
+            NorthEurope,
+            NorwayEast,
+            SouthAfricaNorth,
+            SouthCentralUs,
+            SouthEastasia,
+            SwitzerlandNorth,
+            UaeNorth,
+            UkSouth,
+            UkWest,
+            WestCentralUs,
+            WestEurope,
+            WestUs,
+            WestUs2,
+            WestUs3,
+        );
+        assert_eq!(AzureRegion::ALL, azure);
+    }
+}
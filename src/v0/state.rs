@@ -21,6 +21,8 @@ pub struct State {
     pub provisioning_status: ProvisioningStatus,
     pub allowed_clusters: Option<Vec<ClusterName>>,
     pub condition: Condition,
+    #[serde(flatten)]
+    pub metadata: Metadata,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -34,6 +36,8 @@ pub struct CreateStateDto {
     pub locations: CreateStateLocationsDto,
     pub owner: Option<ClusterName>,
     pub allowed_clusters: Option<Vec<ClusterName>>,
+    #[serde(flatten)]
+    pub metadata: Metadata,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -90,6 +90,7 @@ impl State {
             provisioning_status: ProvisioningStatus::default(),
             allowed_clusters: None,
             condition: Condition::Green,
+            metadata: Metadata::default(),
         }
     }
 
@@ -107,6 +108,36 @@ impl State {
         aws.chain(azure).collect()
     }
 
+    /// No allowed clusters list means any cluster may use the state
+    pub(crate) fn is_cluster_allowed(&self, cluster: &ClusterName) -> bool {
+        match self.allowed_clusters {
+            Some(ref allowed) => allowed.contains(cluster),
+            None => true,
+        }
+    }
+
+    /// Name of the k8s storage class backed by this state
+    pub(crate) fn storage_class_name(&self) -> String {
+        self.storage_class
+            .as_ref()
+            .map_or_else(|| self.name.to_string(), |sc| sc.name.clone())
+    }
+
+    /// Storage class of this state, with the defaults when not configured explicitly
+    pub(crate) fn storage_class(&self) -> StorageClass {
+        self.storage_class
+            .clone()
+            .unwrap_or_else(|| StorageClass::new(self.name.to_string()))
+    }
+
+    fn show_allowed_clusters(&self) -> String {
+        match self.allowed_clusters {
+            Some(ref allowed) if allowed.is_empty() => String::from("None"),
+            Some(ref allowed) => allowed.iter().join(", "),
+            None => String::from("Any"),
+        }
+    }
+
     fn show_owner(&self) -> String {
         self.owner.as_ref().map_or_else(
             || format!("{}", Self::UNOWNED),
@@ -215,6 +246,16 @@ impl StateLocationStatus {
     }
 }
 
+impl StorageClass {
+    pub(crate) fn new(name: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+            fs_type: VolumeFileSystem::Ext4.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
 impl Default for VolumeBindingMode {
     fn default() -> Self {
         Self::WaitForFirstConsumer
@@ -255,6 +296,21 @@ impl StateLocations {
             Location::Azure(region) => self.azure.iter().any(|azure| azure.region == *region),
         }
     }
+
+    pub(crate) fn status(&self, location: &Location) -> Option<StateLocationStatus> {
+        match location {
+            Location::Aws(region) => self
+                .aws
+                .iter()
+                .find(|aws| aws.region == *region)
+                .map(|aws| aws.status),
+            Location::Azure(region) => self
+                .azure
+                .iter()
+                .find(|azure| azure.region == *region)
+                .map(|azure| azure.status),
+        }
+    }
 }
 
 impl Condition {
@@ -25,11 +25,14 @@ impl Show for State {
             .map(|sc| format!("{} ({})", sc.name, sc.fs_type))
             .unwrap_or_default();
         format!(
-            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
             format_args!("State:         {}", self.name),
             format_args!("Id:            {}", self.id),
             format_args!("Storage Class: {}", storage_class),
             format_args!("Owner:         {}", self.show_owner()),
+            format_args!("Allowed:       {}", self.show_allowed_clusters()),
+            format_args!("Labels:        {}", self.metadata.labels.show()),
+            format_args!("Annotations:   {}", self.metadata.annotations.show()),
             format_args!("Created:       {}", HumanTime::from(self.created)),
             format_args!("Modified:      {}", HumanTime::from(self.modified)),
             format_args!("Condition:     {}", self.condition.show()),
@@ -19,6 +19,8 @@ pub struct Volume {
     pub format: Option<DateTime<Utc>>,
     pub created: DateTime<Utc>,
     pub modified: DateTime<Utc>,
+    #[serde(flatten)]
+    pub metadata: Metadata,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -56,8 +58,22 @@ pub struct CreateVolumeDto {
     pub name: String,
     pub size_gi: u64,
     pub fs_type: String,
+    #[serde(flatten)]
+    pub metadata: Metadata,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResizeVolumeDto {
+    pub size_gi: u64,
+}
+
+/// Volume size with binary units, e.g. 500Mi, 20Gi or 1Ti, kept in MiB
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct VolumeSize(u64);
+
 #[derive(
     Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
 )]
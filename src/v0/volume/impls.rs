@@ -6,6 +6,7 @@
 use std::fmt;
 use std::str;
 
+use chrono_humanize::HumanTime;
 use thiserror::Error;
 
 use super::*;
@@ -56,6 +57,11 @@ impl InvalidVolumeFileSystem {
 }
 
 impl VolumeFileSystem {
+    /// Swap and FAT volumes cannot back a regular k8s persistent volume
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, Self::Swap | Self::Fat | Self::Fat32)
+    }
+
     fn as_str(&self) -> &'static str {
         match self {
             Self::Ext => "ext",
@@ -95,6 +101,120 @@ impl fmt::Display for VolumeFileSystem {
     }
 }
 
+#[derive(Debug, Error)]
+#[error(r#"Invalid volume size "{size}", expected e.g. 500Mi, 20Gi or 1Ti"#)]
+pub struct InvalidVolumeSize {
+    size: String,
+}
+
+impl InvalidVolumeSize {
+    pub(crate) fn new(size: &str) -> Self {
+        let size = size.to_string();
+        Self { size }
+    }
+}
+
+impl VolumeSize {
+    const MI: u64 = 1;
+    const GI: u64 = 1024 * Self::MI;
+    const TI: u64 = 1024 * Self::GI;
+
+    pub fn from_gi(size_gi: u64) -> Self {
+        Self(size_gi * Self::GI)
+    }
+
+    /// Size in whole GiB as the API expects it, rounded up
+    pub fn size_gi(&self) -> u64 {
+        self.0.div_ceil(Self::GI)
+    }
+
+    pub fn is_whole_gi(&self) -> bool {
+        self.0.is_multiple_of(Self::GI)
+    }
+}
+
+impl str::FromStr for VolumeSize {
+    type Err = InvalidVolumeSize;
+
+    /// Size without units is in GiB, as it used to be
+    fn from_str(size: &str) -> Result<Self, Self::Err> {
+        let digits = size
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(size.len());
+        let (value, unit) = size.split_at(digits);
+        let unit = match unit {
+            "Mi" => Self::MI,
+            "" | "Gi" => Self::GI,
+            "Ti" => Self::TI,
+            _ => return Err(InvalidVolumeSize::new(size)),
+        };
+        value
+            .parse::<u64>()
+            .ok()
+            .and_then(|value| value.checked_mul(unit))
+            .filter(|size| *size > 0)
+            .map(Self)
+            .ok_or_else(|| InvalidVolumeSize::new(size))
+    }
+}
+
+impl fmt::Display for VolumeSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_multiple_of(Self::TI) {
+            write!(f, "{}Ti", self.0 / Self::TI)
+        } else if self.0.is_multiple_of(Self::GI) {
+            write!(f, "{}Gi", self.0 / Self::GI)
+        } else {
+            write!(f, "{}Mi", self.0)
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error(
+    r#"Invalid volume binding mode "{binding_mode}", expected WaitForFirstConsumer or Immediate"#
+)]
+pub struct InvalidVolumeBindingMode {
+    binding_mode: String,
+}
+
+impl InvalidVolumeBindingMode {
+    pub(crate) fn new(mode: &str) -> Self {
+        let binding_mode = mode.to_string();
+        Self { binding_mode }
+    }
+}
+
+impl VolumeBindingMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::WaitForFirstConsumer => "WaitForFirstConsumer",
+            Self::Immediate => "Immediate",
+        }
+    }
+}
+
+impl str::FromStr for VolumeBindingMode {
+    type Err = InvalidVolumeBindingMode;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode.to_lowercase().as_str() {
+            "waitforfirstconsumer" | "wait-for-first-consumer" | "wait" => {
+                Ok(Self::WaitForFirstConsumer)
+            }
+            "immediate" => Ok(Self::Immediate),
+            _ => Err(InvalidVolumeBindingMode::new(mode)),
+        }
+    }
+}
+
+impl fmt::Display for VolumeBindingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.as_str();
+        text.fmt(f)
+    }
+}
+
 impl VolumeStatus {
     fn as_str(&self) -> &'static str {
         match self {
@@ -147,11 +267,67 @@ impl Show for LocationVolumeStatus {
     }
 }
 
+impl StateLocationVolumeProgress {
+    pub fn is_complete(&self) -> bool {
+        self.bytes_synchronized >= self.bytes_total
+    }
+}
+
+impl VolumeLocation {
+    pub(crate) fn location(&self) -> Option<Location> {
+        self.name.parse().ok()
+    }
+
+    /// Location is fine and has no outstanding data to synchronize
+    pub fn is_synchronized(&self) -> bool {
+        self.status.value == StateLocationStatus::Ok
+            && self
+                .progress
+                .iter()
+                .all(StateLocationVolumeProgress::is_complete)
+    }
+}
+
+impl Show for VolumeLocation {
+    fn show(&self) -> String {
+        let progress = self
+            .progress
+            .as_ref()
+            .map(|progress| format!(" ({} done)", progress.show()))
+            .unwrap_or_default();
+        format!("{}{}", self.status.show(), progress)
+    }
+
+    fn detailed_show(&self) -> String {
+        let progress = self
+            .progress
+            .as_ref()
+            .map_or_else(|| String::from("None"), |progress| progress.detailed_show());
+        format!(
+            " {}:\n  {}\n  {}",
+            self.name,
+            format_args!("Status  : {}", self.status.show()),
+            format_args!("Progress: {}", progress),
+        )
+    }
+}
+
 impl Show for StateLocationVolumeProgress {
     fn show(&self) -> String {
-        let progress = 100 * self.bytes_synchronized / self.bytes_total;
+        let progress = (100 * self.bytes_synchronized)
+            .checked_div(self.bytes_total)
+            .unwrap_or(100);
         format!("{}%", progress)
     }
+
+    fn detailed_show(&self) -> String {
+        format!(
+            "{} ({} of {} bytes)",
+            self.show(),
+            self.bytes_synchronized,
+            self.bytes_total
+        )
+    }
 }
 
 impl Volume {
@@ -161,6 +337,32 @@ impl Volume {
             .any(|location| location.status.is_deleting())
     }
 
+    /// Every location of the volume is up and running
+    pub fn is_ready(&self) -> bool {
+        !self.locations.is_empty()
+            && self
+                .locations
+                .iter()
+                .all(|location| location.status.value == StateLocationStatus::Ok)
+    }
+
+    pub(crate) fn primary_location(&self) -> Option<Location> {
+        self.active_location
+            .as_deref()
+            .and_then(|location| location.parse().ok())
+    }
+
+    /// Volume without active location is not attached to any primary location
+    pub fn show_active_location(&self) -> &str {
+        self.active_location.as_deref().unwrap_or("None (detached)")
+    }
+
+    pub(crate) fn location(&self, location: &Location) -> Option<&VolumeLocation> {
+        self.locations
+            .iter()
+            .find(|volume| volume.location().as_ref() == Some(location))
+    }
+
     pub fn progress(&self) -> Option<(&LocationVolumeStatus, &StateLocationVolumeProgress)> {
         self.locations.iter().find_map(|location| {
             location
@@ -178,7 +380,7 @@ impl Show for Volume {
             self.name,
             self.size_gi,
             self.fs_type,
-            self.active_location.as_deref().unwrap_or("None"),
+            self.show_active_location(),
             self.progress()
                 .map(|(status, progress)| format!(" ({} {} done)", status.show(), progress.show()))
                 .unwrap_or_default()
@@ -186,15 +388,28 @@ impl Show for Volume {
     }
 
     fn detailed_show(&self) -> String {
+        let format = self.format.map_or_else(
+            || String::from("Never"),
+            |format| HumanTime::from(format).to_string(),
+        );
+        let locations = self
+            .locations
+            .iter()
+            .map(VolumeLocation::detailed_show)
+            .join("\n");
         format!(
-            "{}\n{}\n{}\n{}",
-            format_args!("Volume  :{:>60}\n", self.name),
-            format_args!("Size    :{:>56} GiB\n", self.size_gi),
-            format_args!("FS Type :{:>60}\n", self.fs_type),
-            format_args!(
-                "Active  :{:>60}",
-                self.active_location.as_deref().unwrap_or("None")
-            )
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            format_args!("Volume:      {}", self.name),
+            format_args!("Id:          {}", self.id),
+            format_args!("Size:        {} GiB", self.size_gi),
+            format_args!("FS Type:     {}", self.fs_type),
+            format_args!("Active:      {}", self.show_active_location()),
+            format_args!("Formatted:   {}", format),
+            format_args!("Labels:      {}", self.metadata.labels.show()),
+            format_args!("Annotations: {}", self.metadata.annotations.show()),
+            format_args!("Created:     {}", HumanTime::from(self.created)),
+            format_args!("Modified:    {}", HumanTime::from(self.modified)),
+            format_args!("Locations:\n{}", locations),
         )
     }
 }
@@ -204,3 +419,46 @@ impl Show for Vec<Volume> {
         self.iter().map(Show::show).join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_size() {
+        let size = "500Mi".parse::<VolumeSize>().unwrap();
+        assert_eq!(size.size_gi(), 1);
+        assert!(!size.is_whole_gi());
+        assert_eq!(size.to_string(), "500Mi");
+
+        let size = "20Gi".parse::<VolumeSize>().unwrap();
+        assert_eq!(size, VolumeSize::from_gi(20));
+        assert_eq!(size.to_string(), "20Gi");
+
+        let size = "1Ti".parse::<VolumeSize>().unwrap();
+        assert_eq!(size.size_gi(), 1024);
+        assert_eq!(size.to_string(), "1Ti");
+
+        assert_eq!("10".parse::<VolumeSize>().unwrap().size_gi(), 10);
+        assert_eq!("2048Mi".parse::<VolumeSize>().unwrap().to_string(), "2Gi");
+    }
+
+    #[test]
+    fn progress() {
+        let progress = |bytes_synchronized, bytes_total| StateLocationVolumeProgress {
+            bytes_synchronized,
+            bytes_total,
+        };
+        assert_eq!(progress(42, 100).show(), "42%");
+        assert_eq!(progress(0, 0).show(), "100%");
+        assert!(progress(0, 0).is_complete());
+        assert_eq!(progress(5, 10).detailed_show(), "50% (5 of 10 bytes)");
+    }
+
+    #[test]
+    fn invalid_volume_size() {
+        for invalid in &["", "0Gi", "Gi", "10GB", "1.5Gi", "-1Gi", "20gi"] {
+            assert!(invalid.parse::<VolumeSize>().is_err(), "{}", invalid);
+        }
+    }
+}